@@ -1,6 +1,7 @@
 use fyrox::{
     core::{
-        algebra::{Const, Matrix, Vector2, Vector3},
+        algebra::{Vector2, Vector3},
+        color::Color,
         log::Log,
         pool::Handle,
         reflect::prelude::*,
@@ -8,203 +9,224 @@ use fyrox::{
         visitor::prelude::*,
     },
     graph::{BaseSceneGraph, SceneGraph},
-    gui::{message::MessageDirection, text::TextMessage},
     scene::{
+        base::BaseBuilder,
         dim2::{
             collider::{Collider, ColliderShape},
+            rectangle::{Rectangle, RectangleBuilder},
             rigidbody::RigidBody,
         },
+        graph::Graph,
         node::Node,
+        pivot::PivotBuilder,
+        transform::TransformBuilder,
     },
     script::{ScriptContext, ScriptTrait},
 };
-use rand::random_range;
-use std::cmp;
+use pd_core::{
+    arena::{self, MAX_SPEED},
+    AgentId, LineageNode, SimRng,
+};
+use serde::{Deserialize, Serialize};
 
 use crate::Game;
 
-const MAX_SPEED: f32 = 15.0;
-const MAX_WAIT_TIME: f32 = 5.0;
-const MIN_WAIT_TIME: f32 = 3.0;
-const BASE_SIZE: f32 = 0.5;
-const BASE_HEALTH: i64 = 10;
-const SCALE_FACTOR: f32 = 0.1;
-const BOUNCE_FORCE: f32 = -6.0;
-
-//our values to calcuate health gain
-const GREEDGREED_HEALTH_GAIN: i64 = -1;
-const GREEDCOOP_HEALTH_GAIN: i64 = 3;
-const COOPGREED_HEALTH_GAIN: i64 = -2;
-const COOPCOOP_HEALTH_GAIN: i64 = 2;
-
-//our enum that determines the personality type of our bugster
-#[derive(Visit, Reflect, Debug, Clone, Default)]
-pub enum PersonalityType {
-    Greedy,
-    #[default]
-    Cooperative,
+const SPRITE_DEPTH: f32 = 1.0;
+//the health bar keeps the same on screen size whatever the bugster's size
+const HEALTH_BAR_WIDTH: f32 = 0.5;
+const HEALTH_BAR_HEIGHT: f32 = 0.07;
+const HEALTH_BAR_GAP: f32 = 0.08;
+const HEALTH_BAR_DEPTH: f32 = 0.5;
+//the health a full bar stands for, bugsters above it keep a full bar
+const HEALTH_BAR_MAX: i64 = arena::BASE_HEALTH * 2;
+
+//the scene nodes that make up one bugster
+#[derive(Visit, Reflect, Default, Debug, Clone, Copy)]
+pub struct BugsterNodes {
+    pub rigid_body: Handle<Node>,
+    pub collision: Handle<Node>,
+    pub detector: Handle<Node>,
+    pub sprite: Handle<Node>,
+    pub health_bar: Handle<Node>,
+    pub health_fill: Handle<Node>,
 }
 
 #[derive(Visit, Reflect, Default, Debug, Clone, TypeUuidProvider, ComponentProvider)]
 #[type_uuid(id = "9b4ca1b0-d66b-472e-9dcc-8700d6a55b55")]
 #[visit(optional)]
 pub struct Bugsters {
-    pub healthpoints: i64,
-    pub personality: PersonalityType,
+    pub agent: AgentId,
+    //the bugster this one split off from, how many splits separate it from its founder and
+    //the tick it appeared
+    pub parent: Option<AgentId>,
+    pub generation: u32,
+    pub born: u64,
+    pub nodes: BugsterNodes,
     speed: f32,
     x_speed: f32,
     y_speed: f32,
-    move_time_since_last_change: f32,
-    move_change_interval: f32,
-    collision_time_since_last_change: f32,
-    collision_change_interval: f32,
-    rigid_body_handle: Handle<Node>,
-    collision_handle: Handle<Node>,
-    detector_handle: Handle<Node>,
+    next_move_tick: u64,
+    //positions at the last two ticks, the sprite is drawn between them
+    previous_position: Vector2<f32>,
+    current_position: Vector2<f32>,
+}
+
+//what a snapshot keeps of a bugster on top of its agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BugsterState {
+    pub agent: AgentId,
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub scale: f32,
+    pub half_extent: f32,
+    speed: f32,
+    x_speed: f32,
+    y_speed: f32,
+    next_move_tick: u64,
+    previous_position: [f32; 2],
+    current_position: [f32; 2],
 }
 
 impl Bugsters {
     //create a new bugster with the passed in args
-    pub fn new(
-        healthpoints: i64,
-        personality: PersonalityType,
-        rigid_body: Handle<Node>,
-        collision: Handle<Node>,
-        detector: Handle<Node>,
-    ) -> Self {
+    pub fn new(lineage: &LineageNode, nodes: BugsterNodes, position: Vector2<f32>) -> Self {
         Self {
-            healthpoints,
-            personality,
+            agent: lineage.id,
+            parent: lineage.parent,
+            generation: lineage.generation,
+            born: lineage.born,
+            nodes,
             speed: MAX_SPEED,
             x_speed: 0.0,
             y_speed: 0.0,
-            move_time_since_last_change: 2.0,
-            move_change_interval: 1.0,
-            collision_time_since_last_change: 1.0,
-            collision_change_interval: 0.5,
-
-            rigid_body_handle: rigid_body,
-            collision_handle: collision,
-            detector_handle: detector,
+            next_move_tick: 0,
+            previous_position: position,
+            current_position: position,
         }
     }
 
-    //checks for entity collision
-    fn entity_contact(&mut self, context: &mut ScriptContext) {
-        //gets all intersected colliders
-        let Some(detector) = context
-            .scene
-            .graph
-            .try_get_of_type::<Collider>(self.detector_handle)
-        else {
-            return;
+    //advances the movement timer by one tick, returning the impulse to apply when it is time
+    //to change direction
+    pub fn movement_tick(&mut self, tick: u64, rng: &mut SimRng) -> Option<Vector2<f32>> {
+        if tick < self.next_move_tick {
+            return None;
+        }
+        //randomly generate new x and y speeds within the speed limit and a new change interval
+        let ([x_speed, y_speed], wait) = arena::wander(self.speed, rng);
+        self.x_speed = x_speed;
+        self.y_speed = y_speed;
+        self.next_move_tick = tick + wait;
+
+        Some(Vector2::new(self.x_speed, self.y_speed))
+    }
+
+    //captures the bugster and its rigid body for a snapshot
+    pub fn save(&self, graph: &Graph) -> Option<BugsterState> {
+        let rigid_body = graph.try_get_of_type::<RigidBody>(self.nodes.rigid_body)?;
+        let position = rigid_body.local_transform().position();
+        let velocity = rigid_body.lin_vel();
+        let (scale, half_extent) = self.nodes.size(graph)?;
+        Some(BugsterState {
+            agent: self.agent,
+            position: [position.x, position.y],
+            velocity: [velocity.x, velocity.y],
+            scale,
+            half_extent,
+            speed: self.speed,
+            x_speed: self.x_speed,
+            y_speed: self.y_speed,
+            next_move_tick: self.next_move_tick,
+            previous_position: self.previous_position.into(),
+            current_position: self.current_position.into(),
+        })
+    }
+
+    //picks up the movement of a saved bugster, the rigid body is restored by the caller
+    pub fn restore(&mut self, state: &BugsterState) {
+        self.speed = state.speed;
+        self.x_speed = state.x_speed;
+        self.y_speed = state.y_speed;
+        self.next_move_tick = state.next_move_tick;
+        self.previous_position = state.previous_position.into();
+        self.current_position = state.current_position.into();
+    }
+
+    //stores where the body ended up this tick
+    pub fn record_position(&mut self, position: Vector2<f32>) {
+        self.previous_position = self.current_position;
+        self.current_position = position;
+    }
+}
+
+impl BugsterNodes {
+    //builds the health bar, a dark background quad with a coloured quad on top, returning
+    //the bar and the fill, the bar still has to be attached to the bugster
+    pub fn build_health_bar(graph: &mut Graph) -> (Handle<Node>, Handle<Node>) {
+        let quad = |graph: &mut Graph, depth: f32, color: Color| {
+            RectangleBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_scale(Vector3::new(HEALTH_BAR_WIDTH, HEALTH_BAR_HEIGHT, 1.0))
+                        .with_local_position(Vector3::new(0.0, 0.0, depth))
+                        .build(),
+                ),
+            )
+            .with_color(color)
+            .build(graph)
+        };
+        let background = quad(graph, 0.01, Color::opaque(30, 30, 30));
+        let fill = quad(graph, 0.0, Color::GREEN);
+        let bar =
+            PivotBuilder::new(BaseBuilder::new().with_children(&[background, fill])).build(graph);
+        (bar, fill)
+    }
+
+    //gets the rigid bodies of every bugster this one's detector is touching
+    pub fn touching(&self, graph: &Graph) -> Vec<Handle<Node>> {
+        let Some(detector) = graph.try_get_of_type::<Collider>(self.detector) else {
+            return Vec::new();
         };
 
-        //intersections is a vector of instersecting collider pairs
-        let intersections: Vec<_> = detector
-            .intersects(&context.scene.graph.physics2d)
+        //intersections are pairs of colliders, keep the one that isn't ours and get its
+        //parent which should be a rigid body
+        detector
+            .intersects(&graph.physics2d)
             .filter(|i| i.has_any_active_contact)
-            .collect();
-
-        for intersection in intersections {
-            //get the collider that this collider interesected
-            let collided = if self.detector_handle == intersection.collider1 {
-                intersection.collider2
-            } else {
-                intersection.collider1
-            };
-
-            //gets the parent of the collider which should be a rigid body
-            let parent_rigid = {
-                let graph = &context.scene.graph;
-                if let Some(collider) = graph.try_get_of_type::<Collider>(collided) {
-                    collider.parent()
+            .filter_map(|intersection| {
+                let collided = if self.detector == intersection.collider1 {
+                    intersection.collider2
                 } else {
-                    return;
-                }
-            };
-            //apply the health change
-            self.apply_health(parent_rigid, context);
-
-            //get the direction of where the the two colliders touch
-            let direction = match self.get_direction(context, collided) {
-                Some(d) => d,
-                None => return,
-            };
-
-            //use the direction to apply a knockback force that knocks the two nodes away from eachother
-            self.apply_bounce(context, direction.x, direction.y);
-        }
-
-        //recalcualte the size based on the current health
-        self.change_size(context);
+                    intersection.collider1
+                };
+                graph
+                    .try_get_of_type::<Collider>(collided)
+                    .map(|collider| collider.parent())
+            })
+            .collect()
     }
 
-    //apply the change in health base on the personalities of the contacted monsters
-    fn apply_health(&mut self, parent_rigid: Handle<Node>, context: &mut ScriptContext) {
-        let Some(script) = context
-            .scene
-            .graph
-            .try_get_script_of_mut::<Bugsters>(parent_rigid)
-        else {
+    //apply a knockback impulse that pushes this bugster away from the other one
+    pub fn bounce_from(&self, graph: &mut Graph, other: &BugsterNodes) {
+        //get the direction of where the the two bugsters touch
+        let Some(direction) = self.get_direction(graph, other.detector) else {
             return;
         };
-        //gets the personality of the contacted bugster from its script then apply the health change to our bugster
-        let health_change = self.health_calculation(&script.personality);
-        let actual_health_change = cmp::max(health_change, -self.healthpoints); //calcuates the actual amount lost, accounting for health dropping to 0
-        self.healthpoints += health_change;
-        //apply the change to the overall game counters
-        let game = context.plugins.get_mut::<Game>();
-        match self.personality {
-            PersonalityType::Greedy => {
-                game.change_greed_hp(actual_health_change);
-                context
-                    .user_interfaces
-                    .first()
-                    .send_message(TextMessage::text(
-                        game.greed_counter,
-                        MessageDirection::ToWidget,
-                        format!("Greed Total: {}", game.greed_hp).to_owned(),
-                    ));
-            }
-            PersonalityType::Cooperative => {
-                game.change_coop_hp(actual_health_change);
-                context
-                    .user_interfaces
-                    .first()
-                    .send_message(TextMessage::text(
-                        game.coop_counter,
-                        MessageDirection::ToWidget,
-                        format!("Coop Total: {}", game.coop_hp).to_owned(),
-                    ));
-            }
-        }
-        //if hp drops to 0, remove this node
-        if self.healthpoints <= 0 {
-            context.scene.graph.remove_node(self.rigid_body_handle);
-        }
-    }
 
-    //calculates the health changes when contacting another bugster
-    pub fn health_calculation(&mut self, contact_personality: &PersonalityType) -> i64 {
-        match (&self.personality, contact_personality) {
-            (PersonalityType::Greedy, PersonalityType::Greedy) => GREEDGREED_HEALTH_GAIN,
-            (PersonalityType::Greedy, PersonalityType::Cooperative) => GREEDCOOP_HEALTH_GAIN,
-            (PersonalityType::Cooperative, PersonalityType::Greedy) => COOPGREED_HEALTH_GAIN,
-            (PersonalityType::Cooperative, PersonalityType::Cooperative) => COOPCOOP_HEALTH_GAIN,
-        }
+        let Some(rigid_body) = graph.try_get_mut_of_type::<RigidBody>(self.rigid_body) else {
+            Log::info("Not a Rigid Body!");
+            return;
+        };
+
+        rigid_body.apply_impulse(Vector2::new(
+            direction.x * arena::BOUNCE_FORCE,
+            direction.y * arena::BOUNCE_FORCE,
+        ));
     }
 
     //gets the direction of the collided bugster in relation to this bugster
-    fn get_direction(
-        &self,
-        context: &mut ScriptContext,
-        collided: Handle<Node>,
-    ) -> Option<Matrix<f32, Const<3>, Const<1>, fyrox::core::algebra::ArrayStorage<f32, 3, 1>>>
-    {
-        let graph = &context.scene.graph;
+    fn get_direction(&self, graph: &Graph, collided: Handle<Node>) -> Option<Vector3<f32>> {
         let contracted_node = graph.try_get(collided)?;
-        let self_node = graph.try_get(self.detector_handle)?;
+        let self_node = graph.try_get(self.detector)?;
 
         let self_position = self_node.global_position();
         let contracted_position = contracted_node.global_position();
@@ -212,110 +234,111 @@ impl Bugsters {
         Some(contracted_position - self_position)
     }
 
-    //apply a impuluse in a given direction x and y
-    fn apply_bounce(&mut self, context: &mut ScriptContext, direction_x: f32, direction_y: f32) {
-        let Some(rigid_body) = context
-            .scene
-            .graph
-            .try_get_mut_of_type::<RigidBody>(self.rigid_body_handle)
-        else {
-            Log::info("Not a Rigid Body!");
-            return;
-        };
+    //changes the size of the bugster based on the health
+    pub fn change_size(&self, graph: &mut Graph, healthpoints: i64) {
+        let change_scale = arena::body_size(healthpoints);
 
-        rigid_body.apply_impulse(Vector2::new(
-            direction_x * BOUNCE_FORCE,
-            direction_y * BOUNCE_FORCE,
-        ));
+        self.set_size(graph, change_scale, change_scale / 2.0);
+        self.update_health_bar(graph, healthpoints, change_scale);
     }
 
-    //changes the size of the bugster based on the health
-    pub fn change_size(&mut self, context: &mut ScriptContext) {
-        //calcuates the size change based on a scaling equation
-        let change_scale: f32 = if self.healthpoints >= BASE_HEALTH {
-            SCALE_FACTOR * (self.healthpoints as f32 - BASE_HEALTH as f32).sqrt() + BASE_SIZE
-        } else {
-            -SCALE_FACTOR * (-self.healthpoints as f32 + BASE_HEALTH as f32).sqrt() + BASE_SIZE
-        };
+    //the scale of the rigid body and the half size of its colliders
+    pub fn size(&self, graph: &Graph) -> Option<(f32, f32)> {
+        let scale = graph.try_get(self.rigid_body)?.local_transform().scale().x;
+        match graph.try_get_of_type::<Collider>(self.collision)?.shape() {
+            ColliderShape::Cuboid(cuboid) => Some((scale, cuboid.half_extents.x)),
+            _ => None,
+        }
+    }
 
-        if let Some(rigid_body) = context
-            .scene
-            .graph
-            .try_get_mut_of_type::<RigidBody>(self.rigid_body_handle)
-        {
-            rigid_body.local_transform_mut().set_scale(Vector3::new(
-                change_scale,
-                change_scale,
-                1.0,
-            ));
+    //sets the scale of the rigid body and the half size of its colliders
+    pub fn set_size(&self, graph: &mut Graph, scale: f32, half_extent: f32) {
+        if let Some(rigid_body) = graph.try_get_mut_of_type::<RigidBody>(self.rigid_body) {
+            rigid_body
+                .local_transform_mut()
+                .set_scale(Vector3::new(scale, scale, 1.0));
         } else {
             Log::info("Not a Rigid Body!")
         };
 
         //Fyrox requires you to provide the colliders with a new shape to change size despite the parent rigidbody changing
-        if let Some(collider) = context
-            .scene
-            .graph
-            .try_get_mut_of_type::<Collider>(self.collision_handle)
-        {
-            collider.set_shape(ColliderShape::cuboid(
-                change_scale / 2.0,
-                change_scale / 2.0,
-            ));
+        if let Some(collider) = graph.try_get_mut_of_type::<Collider>(self.collision) {
+            collider.set_shape(ColliderShape::cuboid(half_extent, half_extent));
         } else {
             Log::info("Not a Collider");
             return;
         };
 
-        if let Some(collider) = context
-            .scene
-            .graph
-            .try_get_mut_of_type::<Collider>(self.detector_handle)
-        {
-            collider.set_shape(ColliderShape::cuboid(
-                change_scale / 2.0,
-                change_scale / 2.0,
-            ));
+        if let Some(collider) = graph.try_get_mut_of_type::<Collider>(self.detector) {
+            collider.set_shape(ColliderShape::cuboid(half_extent, half_extent));
         } else {
             Log::info("Not a Collider");
         }
     }
+
+    //fills the health bar to match the health, `body_scale` is the scale of the rigid body
+    //which is undone so the bar stays the same size
+    pub fn update_health_bar(&self, graph: &mut Graph, healthpoints: i64, body_scale: f32) {
+        let body_scale = body_scale.max(f32::EPSILON);
+        if let Some(bar) = graph.try_get_mut(self.health_bar) {
+            bar.local_transform_mut()
+                .set_position(health_bar_position(body_scale, Vector2::zeros()))
+                .set_scale(Vector3::new(1.0 / body_scale, 1.0 / body_scale, 1.0));
+        }
+
+        let fraction = (healthpoints as f32 / HEALTH_BAR_MAX as f32).clamp(0.0, 1.0);
+        let Some(fill) = graph.try_get_mut_of_type::<Rectangle>(self.health_fill) else {
+            return;
+        };
+        //shrink towards the left edge and fade from green to red
+        let width = HEALTH_BAR_WIDTH * fraction;
+        fill.local_transform_mut()
+            .set_position(Vector3::new((width - HEALTH_BAR_WIDTH) / 2.0, 0.0, 0.0))
+            .set_scale(Vector3::new(width, HEALTH_BAR_HEIGHT, 1.0));
+        fill.set_color(Color::opaque(
+            (255.0 * (1.0 - fraction)) as u8,
+            (255.0 * fraction) as u8,
+            0,
+        ));
+    }
 }
 
 impl ScriptTrait for Bugsters {
     fn on_init(&mut self, _context: &mut ScriptContext) {}
 
+    //the simulation itself runs in fixed ticks from the game plugin, every frame only the
+    //sprite and its health bar are moved between the positions of the last two ticks
     fn on_update(&mut self, context: &mut ScriptContext) {
-        //check for collision
-        if self.collision_time_since_last_change >= self.collision_change_interval {
-            self.collision_time_since_last_change = 0.0;
-            self.entity_contact(context);
-        }
-
-        let Some(rigid_body) = context
-            .scene
-            .graph
-            .try_get_mut_of_type::<RigidBody>(self.rigid_body_handle)
+        let alpha = context.plugins.get::<Game>().simulation.clock.alpha();
+        let graph = &mut context.scene.graph;
+        let Some(scale) = graph
+            .try_get(self.nodes.rigid_body)
+            .map(|body| body.local_transform().scale().x)
         else {
-            Log::info("Not a Rigid Body!");
             return;
         };
 
-        self.move_time_since_last_change += context.dt;
-        self.collision_time_since_last_change += context.dt;
-
-        //when the time since last change exceeds the change interval, change direction and apply impulse
-        if self.move_time_since_last_change >= self.move_change_interval {
-            //randomly generate new x and y speeds within the speed limit
-            self.x_speed = random_range(-self.speed..=self.speed);
-            self.y_speed = random_range(-1.0..=1.0) * (self.speed - self.x_speed.abs());
-            //reset the timer
-            self.move_time_since_last_change = 0.0;
-            //set a new random change interval
-            self.move_change_interval = random_range(MIN_WAIT_TIME..=MAX_WAIT_TIME);
-
-            //apply the new speeds as an impulse to the rigid body
-            rigid_body.apply_impulse(Vector2::new(self.x_speed, self.y_speed));
+        let drawn_position = self.previous_position.lerp(&self.current_position, alpha);
+        let offset = (drawn_position - self.current_position) / scale.max(f32::EPSILON);
+        if let Some(sprite) = graph.try_get_mut(self.nodes.sprite) {
+            sprite.local_transform_mut().set_position(Vector3::new(
+                offset.x,
+                offset.y,
+                SPRITE_DEPTH,
+            ));
+        }
+        if let Some(bar) = graph.try_get_mut(self.nodes.health_bar) {
+            bar.local_transform_mut()
+                .set_position(health_bar_position(scale, offset));
         }
     }
 }
+
+//where the health bar sits in the rigid body's space, just above the sprite
+fn health_bar_position(body_scale: f32, sprite_offset: Vector2<f32>) -> Vector3<f32> {
+    Vector3::new(
+        sprite_offset.x,
+        sprite_offset.y + 0.5 + HEALTH_BAR_GAP / body_scale.max(f32::EPSILON),
+        HEALTH_BAR_DEPTH,
+    )
+}
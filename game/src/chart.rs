@@ -0,0 +1,311 @@
+use fyrox::{
+    core::{algebra::Vector2, color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        build::BuildContext,
+        button::ButtonBuilder,
+        grid::{Column, GridBuilder, Row},
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        vector_image::{Primitive, VectorImage, VectorImageBuilder},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use pd_core::{Sample, StrategyId, StrategyRegistry, TimeSeries};
+
+use crate::hud;
+
+const PLOT_WIDTH: f32 = 320.0;
+const PLOT_HEIGHT: f32 = 160.0;
+const LINE_THICKNESS: f32 = 2.0;
+const AXIS_THICKNESS: f32 = 1.0;
+const LABEL_FONT_SIZE: f32 = 14.0;
+//long runs are thinned out to at most this many points per line
+const MAX_POINTS: usize = 300;
+
+//what the chart is plotting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChartMetric {
+    #[default]
+    Alive,
+    TotalHp,
+}
+
+impl ChartMetric {
+    fn label(self) -> &'static str {
+        match self {
+            ChartMetric::Alive => "Alive",
+            ChartMetric::TotalHp => "Total HP",
+        }
+    }
+
+    fn value(self, sample: &Sample) -> f32 {
+        match self {
+            ChartMetric::Alive => sample.alive as f32,
+            ChartMetric::TotalHp => sample.total_hp.max(0) as f32,
+        }
+    }
+
+    fn toggled(self) -> Self {
+        match self {
+            ChartMetric::Alive => ChartMetric::TotalHp,
+            ChartMetric::TotalHp => ChartMetric::Alive,
+        }
+    }
+}
+
+//a line chart of the population over time, one line per strategy
+#[derive(Debug, Default)]
+pub struct PopulationChart {
+    pub panel: Handle<UiNode>,
+    pub toggle: Handle<UiNode>,
+    title: Handle<UiNode>,
+    y_max_text: Handle<UiNode>,
+    x_range_text: Handle<UiNode>,
+    lines: Vec<(StrategyId, Handle<UiNode>)>,
+    metric: ChartMetric,
+    //how many samples were drawn last time, the chart is only redrawn when new ones arrive
+    drawn: usize,
+}
+
+impl PopulationChart {
+    //builds the hidden chart panel in the bottom right corner of the screen
+    pub fn build(
+        ui: &mut UserInterface,
+        strategies: &StrategyRegistry,
+        screen: Handle<UiNode>,
+    ) -> Self {
+        let ctx = &mut ui.build_ctx();
+        let metric = ChartMetric::default();
+
+        let title = build_label(ctx, metric.label());
+        let toggle = ButtonBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(2.0))
+                .with_horizontal_alignment(HorizontalAlignment::Right),
+        )
+        .with_text("Toggle metric")
+        .build(ctx);
+        let header = StackPanelBuilder::new(WidgetBuilder::new().with_children([title, toggle]))
+            .with_orientation(Orientation::Horizontal)
+            .build(ctx);
+
+        //the axes sit under one line per strategy, all stacked in the same grid cell
+        let axes = build_plot_image(
+            ctx,
+            Color::opaque(160, 160, 160),
+            vec![
+                Primitive::Line {
+                    begin: Vector2::new(0.0, 0.0),
+                    end: Vector2::new(0.0, PLOT_HEIGHT),
+                    thickness: AXIS_THICKNESS,
+                },
+                Primitive::Line {
+                    begin: Vector2::new(0.0, PLOT_HEIGHT),
+                    end: Vector2::new(PLOT_WIDTH, PLOT_HEIGHT),
+                    thickness: AXIS_THICKNESS,
+                },
+            ],
+        );
+        let lines: Vec<_> = strategies
+            .iter()
+            .map(|(id, strategy)| {
+                (
+                    id,
+                    build_plot_image(ctx, hud::strategy_color(strategy), Vec::new()),
+                )
+            })
+            .collect();
+        let y_max_text = build_label(ctx, "0");
+        let plot = GridBuilder::new(
+            WidgetBuilder::new()
+                .with_child(axes)
+                .with_children(lines.iter().map(|(_, line)| *line))
+                .with_child(y_max_text),
+        )
+        .add_row(Row::strict(PLOT_HEIGHT))
+        .add_column(Column::strict(PLOT_WIDTH))
+        .build(ctx);
+
+        let x_range_text = build_label(ctx, "Tick 0");
+        let legend = StackPanelBuilder::new(WidgetBuilder::new().with_children(
+            strategies.iter().map(|(_, strategy)| {
+                TextBuilder::new(
+                    WidgetBuilder::new()
+                        .with_margin(Thickness::uniform(2.0))
+                        .with_foreground(Brush::Solid(hud::strategy_color(strategy)).into()),
+                )
+                .with_font_size(LABEL_FONT_SIZE.into())
+                .with_text(strategy.name())
+                .build(ctx)
+            }),
+        ))
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+
+        let content = StackPanelBuilder::new(
+            WidgetBuilder::new()
+                .with_margin(Thickness::uniform(4.0))
+                .with_children([header, plot, x_range_text, legend]),
+        )
+        .with_orientation(Orientation::Vertical)
+        .build(ctx);
+        let panel = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_horizontal_alignment(HorizontalAlignment::Right)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_margin(Thickness::uniform(8.0))
+                .with_background(Brush::Solid(Color::from_rgba(0, 0, 0, 160)).into())
+                .with_child(content),
+        )
+        .build(ctx);
+        ui.link_nodes(panel, screen, false);
+
+        Self {
+            panel,
+            toggle,
+            title,
+            y_max_text,
+            x_range_text,
+            lines,
+            metric,
+            drawn: 0,
+        }
+    }
+
+    pub fn set_visible(&self, ui: &UserInterface, visible: bool) {
+        ui.send_message(WidgetMessage::visibility(
+            self.panel,
+            MessageDirection::ToWidget,
+            visible,
+        ));
+    }
+
+    //switches between plotting the alive count and the total hp
+    pub fn toggle_metric(&mut self, ui: &mut UserInterface, stats: &TimeSeries) {
+        self.metric = self.metric.toggled();
+        ui.send_message(TextMessage::text(
+            self.title,
+            MessageDirection::ToWidget,
+            self.metric.label().to_owned(),
+        ));
+        self.redraw(ui, stats);
+    }
+
+    //empties every line, ready for a new run
+    pub fn clear(&mut self, ui: &mut UserInterface) {
+        self.drawn = 0;
+        for (_, line) in &self.lines {
+            if let Some(image) = ui.try_get_mut_of_type::<VectorImage>(*line) {
+                image.primitives.set_value_and_mark_modified(Vec::new());
+            }
+        }
+    }
+
+    //redraws the lines if new samples were taken since the last call
+    pub fn update(&mut self, ui: &mut UserInterface, stats: &TimeSeries) {
+        if stats.samples().len() != self.drawn {
+            self.redraw(ui, stats);
+        }
+    }
+
+    fn redraw(&mut self, ui: &mut UserInterface, stats: &TimeSeries) {
+        self.drawn = stats.samples().len();
+        let Some(last_tick) = stats.samples().last().map(|s| s.tick) else {
+            return;
+        };
+        let first_tick = stats.samples()[0].tick;
+        let tick_span = (last_tick - first_tick).max(1) as f32;
+
+        //scale the y axis to the largest value on screen so small populations stay readable
+        let y_max = nice_ceiling(
+            stats
+                .samples()
+                .iter()
+                .map(|s| self.metric.value(s))
+                .fold(0.0, f32::max),
+        );
+
+        for (strategy, line) in &self.lines {
+            let samples: Vec<_> = stats.strategy_samples(*strategy).collect();
+            let stride = samples.len().div_ceil(MAX_POINTS).max(1);
+            let mut points: Vec<_> = samples.iter().step_by(stride).copied().collect();
+            //always end on the latest sample
+            if let (Some(last), Some(latest)) = (points.last(), samples.last()) {
+                if last.tick != latest.tick {
+                    points.push(*latest);
+                }
+            }
+
+            let to_plot = |s: &Sample| {
+                Vector2::new(
+                    (s.tick - first_tick) as f32 / tick_span * PLOT_WIDTH,
+                    PLOT_HEIGHT - self.metric.value(s) / y_max * PLOT_HEIGHT,
+                )
+            };
+            let primitives = points
+                .windows(2)
+                .map(|pair| Primitive::Line {
+                    begin: to_plot(pair[0]),
+                    end: to_plot(pair[1]),
+                    thickness: LINE_THICKNESS,
+                })
+                .collect();
+            if let Some(image) = ui.try_get_mut_of_type::<VectorImage>(*line) {
+                image.primitives.set_value_and_mark_modified(primitives);
+            }
+        }
+
+        ui.send_message(TextMessage::text(
+            self.y_max_text,
+            MessageDirection::ToWidget,
+            format!("{y_max}"),
+        ));
+        ui.send_message(TextMessage::text(
+            self.x_range_text,
+            MessageDirection::ToWidget,
+            format!("Tick {first_tick} - {last_tick}"),
+        ));
+    }
+}
+
+//a small line of text used for the title and axis labels
+fn build_label(ctx: &mut BuildContext, text: &str) -> Handle<UiNode> {
+    TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(2.0)))
+        .with_font_size(LABEL_FONT_SIZE.into())
+        .with_text(text)
+        .build(ctx)
+}
+
+//an image the size of the plot area, drawn in one colour
+fn build_plot_image(
+    ctx: &mut BuildContext,
+    color: Color,
+    primitives: Vec<Primitive>,
+) -> Handle<UiNode> {
+    VectorImageBuilder::new(
+        WidgetBuilder::new()
+            .with_width(PLOT_WIDTH)
+            .with_height(PLOT_HEIGHT)
+            .with_foreground(Brush::Solid(color).into()),
+    )
+    .with_primitives(primitives)
+    .build(ctx)
+}
+
+//rounds up to the next 1, 2 or 5 times a power of ten
+fn nice_ceiling(value: f32) -> f32 {
+    if value <= 1.0 {
+        return 1.0;
+    }
+    let magnitude = 10f32.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|step| step * magnitude)
+        .find(|nice| *nice >= value)
+        .unwrap_or(10.0 * magnitude)
+}
@@ -0,0 +1,195 @@
+use fyrox::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        brush::Brush, button::ButtonBuilder, check_box::CheckBoxBuilder,
+        numeric::NumericUpDownBuilder, scroll_viewer::ScrollViewerBuilder,
+        stack_panel::StackPanelBuilder, text::TextBuilder, widget::WidgetBuilder,
+        HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use pd_core::Strategy;
+
+const COUNTER_FONT_SIZE: f32 = 28.0;
+const FIELD_WIDTH: f32 = 100.0;
+const FIELD_HEIGHT: f32 = 36.0;
+const BUTTON_WIDTH: f32 = 80.0;
+//pushes the extra spawn fields below the coop and greed fields from start.ui
+const SPAWN_PANEL_OFFSET: f32 = 420.0;
+
+//the hud colour for a strategy
+pub fn strategy_color(strategy: &dyn Strategy) -> Color {
+    let [r, g, b] = strategy.color();
+    Color::opaque(r, g, b)
+}
+
+//builds a line of hud text in the counter panel
+pub fn build_hud_text(
+    ui: &mut UserInterface,
+    color: Color,
+    text: &str,
+    counter_panel: Handle<UiNode>,
+) -> Handle<UiNode> {
+    let hud_text =
+        TextBuilder::new(WidgetBuilder::new().with_foreground(Brush::Solid(color).into()))
+            .with_font_size(COUNTER_FONT_SIZE.into())
+            .with_text(text)
+            .build(&mut ui.build_ctx());
+    ui.link_nodes(hud_text, counter_panel, false);
+    hud_text
+}
+
+//builds a button inside `parent`
+pub fn build_button(ui: &mut UserInterface, text: &str, parent: Handle<UiNode>) -> Handle<UiNode> {
+    let button = ButtonBuilder::new(
+        WidgetBuilder::new()
+            .with_width(BUTTON_WIDTH)
+            .with_height(FIELD_HEIGHT)
+            .with_horizontal_alignment(HorizontalAlignment::Left),
+    )
+    .with_text(text)
+    .build(&mut ui.build_ctx());
+    ui.link_nodes(button, parent, false);
+    button
+}
+
+//builds an empty row in the counter panel for buttons to be put in
+pub fn build_button_row(ui: &mut UserInterface, counter_panel: Handle<UiNode>) -> Handle<UiNode> {
+    let row = StackPanelBuilder::new(WidgetBuilder::new())
+        .with_orientation(Orientation::Horizontal)
+        .build(&mut ui.build_ctx());
+    ui.link_nodes(row, counter_panel, false);
+    row
+}
+
+//builds a total counter text for a strategy and attaches it to the counter panel
+pub fn build_counter(
+    ui: &mut UserInterface,
+    strategy: &dyn Strategy,
+    counter_panel: Handle<UiNode>,
+) -> Handle<UiNode> {
+    build_hud_text(
+        ui,
+        strategy_color(strategy),
+        &format!("{} Total: 0", strategy.name()),
+        counter_panel,
+    )
+}
+
+//builds the panel that holds the spawn fields not present in start.ui, it scrolls so the menu
+//fits below start.ui however many rows it has, returning the scroll viewer and the panel
+pub fn build_spawn_panel(
+    ui: &mut UserInterface,
+    screen: Handle<UiNode>,
+) -> (Handle<UiNode>, Handle<UiNode>) {
+    let ctx = &mut ui.build_ctx();
+    let panel = StackPanelBuilder::new(WidgetBuilder::new())
+        .with_orientation(Orientation::Vertical)
+        .build(ctx);
+    let scroll_viewer = ScrollViewerBuilder::new(
+        WidgetBuilder::new()
+            .with_horizontal_alignment(HorizontalAlignment::Center)
+            .with_vertical_alignment(VerticalAlignment::Stretch)
+            .with_margin(Thickness::top(SPAWN_PANEL_OFFSET)),
+    )
+    .with_content(panel)
+    .build(ctx);
+    ui.link_nodes(scroll_viewer, screen, false);
+    (scroll_viewer, panel)
+}
+
+//builds a labelled spawn count field for a strategy inside the spawn panel
+pub fn build_spawn_field(
+    ui: &mut UserInterface,
+    strategy: &dyn Strategy,
+    spawn_panel: Handle<UiNode>,
+) -> Handle<UiNode> {
+    let ctx = &mut ui.build_ctx();
+    let label = TextBuilder::new(
+        WidgetBuilder::new()
+            .with_width(FIELD_WIDTH)
+            .with_foreground(Brush::Solid(strategy_color(strategy)).into()),
+    )
+    .with_horizontal_text_alignment(HorizontalAlignment::Center)
+    .with_vertical_text_alignment(VerticalAlignment::Center)
+    .with_text(strategy.name())
+    .build(ctx);
+    let field = NumericUpDownBuilder::<i64>::new(
+        WidgetBuilder::new()
+            .with_width(FIELD_WIDTH)
+            .with_height(FIELD_HEIGHT),
+    )
+    .with_min_value(0)
+    .with_value(0)
+    .build(ctx);
+    let row = StackPanelBuilder::new(WidgetBuilder::new().with_children([label, field]))
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+    ui.link_nodes(row, spawn_panel, false);
+    field
+}
+
+//builds a labelled whole number field at the bottom of the spawn panel, used for the seed
+//and the end conditions
+pub fn build_number_field(
+    ui: &mut UserInterface,
+    text: &str,
+    value: u64,
+    spawn_panel: Handle<UiNode>,
+) -> Handle<UiNode> {
+    let ctx = &mut ui.build_ctx();
+    let label = TextBuilder::new(WidgetBuilder::new().with_width(FIELD_WIDTH))
+        .with_horizontal_text_alignment(HorizontalAlignment::Center)
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .with_text(text)
+        .build(ctx);
+    let field = NumericUpDownBuilder::<u64>::new(
+        WidgetBuilder::new()
+            .with_width(FIELD_WIDTH)
+            .with_height(FIELD_HEIGHT),
+    )
+    .with_value(value)
+    .build(ctx);
+    let row = StackPanelBuilder::new(WidgetBuilder::new().with_children([label, field]))
+        .with_orientation(Orientation::Horizontal)
+        .build(ctx);
+    ui.link_nodes(row, spawn_panel, false);
+    field
+}
+
+//builds a labelled check box at the bottom of the spawn panel
+pub fn build_check_box(
+    ui: &mut UserInterface,
+    text: &str,
+    checked: bool,
+    spawn_panel: Handle<UiNode>,
+) -> Handle<UiNode> {
+    let ctx = &mut ui.build_ctx();
+    let label = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::left(4.0)))
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .with_text(text)
+        .build(ctx);
+    let check_box = CheckBoxBuilder::new(WidgetBuilder::new().with_height(FIELD_HEIGHT))
+        .checked(Some(checked))
+        .with_content(label)
+        .build(ctx);
+    ui.link_nodes(check_box, spawn_panel, false);
+    check_box
+}
+
+//builds a button at the bottom of the spawn panel with the current choice written next to it,
+//returning the button and the text
+pub fn build_choice(
+    ui: &mut UserInterface,
+    text: &str,
+    choice: &str,
+    spawn_panel: Handle<UiNode>,
+) -> (Handle<UiNode>, Handle<UiNode>) {
+    let row = build_button_row(ui, spawn_panel);
+    let button = build_button(ui, text, row);
+    let label = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::left(4.0)))
+        .with_vertical_text_alignment(VerticalAlignment::Center)
+        .with_text(choice)
+        .build(&mut ui.build_ctx());
+    ui.link_nodes(label, row, false);
+    (button, label)
+}
@@ -5,17 +5,27 @@ use fyrox::{
     asset::{manager::ResourceManager, untyped::ResourceKind},
     core::{
         algebra::{Vector2, Vector3},
+        color::Color,
         log::Log,
         pool::Handle,
         reflect::prelude::*,
         uuid,
         visitor::prelude::*,
     },
+    event::{ElementState, Event, WindowEvent},
     graph::SceneGraph,
     gui::{
-        button::ButtonMessage, message::MessageDirection, numeric::NumericUpDown,
-        text::TextMessage, texture::Texture, widget::WidgetMessage, UiNode, UserInterface,
+        button::ButtonMessage,
+        check_box::CheckBox,
+        message::MessageDirection,
+        numeric::{NumericUpDown, NumericUpDownMessage},
+        scroll_bar::ScrollBarMessage,
+        text::TextMessage,
+        texture::Texture,
+        widget::WidgetMessage,
+        UiNode, UserInterface,
     },
+    keyboard::{KeyCode, PhysicalKey},
     material::{Material, MaterialResource},
     plugin::{Plugin, PluginContext, PluginRegistrationContext},
     renderer::QualitySettings,
@@ -25,9 +35,9 @@ use fyrox::{
         dim2::{
             collider::{ColliderBuilder, ColliderShape},
             rectangle::RectangleBuilder,
-            rigidbody::RigidBodyBuilder,
+            rigidbody::{RigidBody, RigidBodyBuilder},
         },
-        graph::Graph,
+        graph::{Graph, GraphUpdateSwitches},
         node::Node,
         rigidbody::RigidBodyType,
         transform::TransformBuilder,
@@ -35,96 +45,1065 @@ use fyrox::{
     },
 };
 
-use rand::random_range;
-use std::path::Path;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 // Re-export the engine.
 pub use fyrox;
 
-use crate::bugster::{Bugsters, PersonalityType};
+use pd_core::{
+    arena,
+    clock::{TICK_DT, TICK_RATE},
+    reproduction::DEFAULT_CAPACITY,
+    rng,
+    strategies::{Evolved, Mixed},
+    AgentId, Birth, EndConditions, EndReason, GameType, Interaction, LineageNode, Noise,
+    PayoffMatrix, PublicGoods, Replay, ReplayPlayer, ReplayRecorder, Reproduction, RunSummary,
+    Simulation, Snapshot, Strategy, StrategyId, StrategyRegistry, TimeSeries,
+};
+
+use crate::{
+    bugster::{BugsterNodes, BugsterState, Bugsters},
+    chart::PopulationChart,
+    summary::SummaryScreen,
+    timeline::Timeline,
+};
 
 //our scripts
 pub mod bugster;
+pub mod chart;
+pub mod hud;
+pub mod summary;
+pub mod timeline;
 
-const MAX_X: f32 = 8.0;
-const MAX_Y: f32 = 3.5;
-const BASE_HEALTH: i64 = 10;
-const BASE_SIZE: f32 = 0.5;
+//the headless arena uses the same sizes so runs without a window stay close to the game
+const MAX_X: f32 = arena::HALF_WIDTH;
+const MAX_Y: f32 = arena::HALF_HEIGHT;
+const BASE_HEALTH: i64 = arena::BASE_HEALTH;
+const BASE_SIZE: f32 = arena::BASE_SIZE;
+//bugsters split in two above this much health unless changed in the start menu
+const DEFAULT_SPLIT_THRESHOLD: u64 = 20;
 
-const COOPERATIVE_SPRITE_PATH: &str = "data/Sprites/bugster_cooperative.png";
-const GREEDY_SPRITE_PATH: &str = "data/Sprites/bugster_greedy.png";
+const BASE_SPRITE_PATH: &str = "data/Sprites/bugster_base.png";
+const PAYOFF_PATH: &str = "data/payoffs.ron";
+//where the payoffs of a custom game are read from
+const CUSTOM_PAYOFF_PATH: &str = "data/payoffs_custom.ron";
+//where exported results go unless `--output <dir>` is given
+const RESULTS_DIR: &str = "results";
+//where runs are saved and loaded from unless `--snapshot <file>` is given
+const SNAPSHOT_PATH: &str = "saves/snapshot.json";
+
+//what a snapshot keeps of the arena and the plugin on top of the simulation
+#[derive(Debug, Serialize, Deserialize)]
+struct ArenaState {
+    bugsters: Vec<BugsterState>,
+    spawn_counts: Vec<(StrategyId, i64)>,
+    end_conditions: EndConditions,
+    ended: Option<EndReason>,
+    #[serde(default)]
+    reproduction: Reproduction,
+    #[serde(default)]
+    interaction: Interaction,
+}
 
 #[derive(Default, Visit, Reflect, Debug)]
 #[reflect(non_cloneable)]
 pub struct Game {
-    pub coop_hp: i64,
-    pub greed_hp: i64,
-    pub coop_counter: Handle<UiNode>,
-    pub greed_counter: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub simulation: Simulation,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    pub strategies: Arc<StrategyRegistry>,
     scene: Handle<Scene>,
     start: Handle<UiNode>,
     exit: Handle<UiNode>,
-    coop_field: Handle<UiNode>,
-    greed_field: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    counters: Vec<(StrategyId, Handle<UiNode>)>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    spawn_fields: Vec<(StrategyId, Handle<UiNode>)>,
+    //the spawn counts of the current run, so it can be reset
+    #[visit(skip)]
+    #[reflect(hidden)]
+    spawn_counts: Vec<(StrategyId, i64)>,
+    //scrolls the spawn panel
+    spawn_scroll: Handle<UiNode>,
+    spawn_panel: Handle<UiNode>,
+    seed_field: Handle<UiNode>,
+    tick_limit_field: Handle<UiNode>,
+    stable_field: Handle<UiNode>,
+    one_left_check: Handle<UiNode>,
+    all_dead_check: Handle<UiNode>,
+    split_field: Handle<UiNode>,
+    capacity_field: Handle<UiNode>,
+    mutation_field: Handle<UiNode>,
+    crossover_field: Handle<UiNode>,
+    point_mutation_field: Handle<UiNode>,
+    execution_field: Handle<UiNode>,
+    perception_field: Handle<UiNode>,
+    public_goods_check: Handle<UiNode>,
+    stake_field: Handle<UiNode>,
+    multiplier_field: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    interaction: Interaction,
+    //the quads marking the public goods zones
+    #[visit(skip)]
+    #[reflect(hidden)]
+    zone_nodes: Vec<Handle<Node>>,
+    game_button: Handle<UiNode>,
+    game_label: Handle<UiNode>,
+    //the game picked in the start menu
+    #[visit(skip)]
+    #[reflect(hidden)]
+    game: GameType,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    end_conditions: EndConditions,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    reproduction: Reproduction,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    noise: Noise,
+    seed_text: Handle<UiNode>,
+    game_text: Handle<UiNode>,
+    tick_text: Handle<UiNode>,
+    export: Handle<UiNode>,
+    reset: Handle<UiNode>,
+    save: Handle<UiNode>,
+    load: Handle<UiNode>,
+    menu: Handle<UiNode>,
+    speed_text: Handle<UiNode>,
+    pause: Handle<UiNode>,
+    step: Handle<UiNode>,
+    slower: Handle<UiNode>,
+    faster: Handle<UiNode>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    chart: PopulationChart,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    summary: SummaryScreen,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    timeline: Timeline,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    recorder: ReplayRecorder,
+    //set in replay mode, the arena then shows the replay instead of a run
+    #[visit(skip)]
+    #[reflect(hidden)]
+    replay: Option<ReplayPlayer>,
+    #[visit(skip)]
+    #[reflect(hidden)]
+    replay_nodes: HashMap<AgentId, (BugsterNodes, i64)>,
+    replay_time: f32,
+    replay_playing: bool,
+    running: bool,
+    //set once an end condition is met, the run stays on screen but no longer ticks
+    #[visit(skip)]
+    #[reflect(hidden)]
+    ended: Option<EndReason>,
 }
 
 impl Game {
-    fn game_start(&mut self, context: &mut PluginContext, coop_count: i64, greed_count: i64) {
+    fn game_start(
+        &mut self,
+        context: &mut PluginContext,
+        seed: u64,
+        spawn_counts: Vec<(StrategyId, i64)>,
+    ) {
+        let payoffs = self.load_payoffs();
+        self.simulation = Simulation::new(seed, payoffs, self.strategies.clone());
+        self.simulation.population.noise = self.noise;
+        self.build_zones(context);
+        //`--sample-every <ticks>` changes how often the time series is sampled
+        if let Some(interval) = arg_value("--sample-every") {
+            match interval.parse() {
+                Ok(interval) => self.simulation.stats = TimeSeries::new(interval),
+                Err(_) => Log::err(format!("Invalid sample interval {interval}")),
+            }
+        }
+        Log::info(format!("Starting run with seed {seed}"));
+        //`--events <file.jsonl>` streams every encounter of the run to a file
+        if let Some(path) = arg_value("--events") {
+            match self.simulation.events.stream_to(&path) {
+                Ok(()) => Log::info(format!("Writing events to {path}")),
+                Err(e) => Log::err(format!("Could not write events to {path}: {e}")),
+            }
+        }
+
         //add our bugsters to the scene with random positions
-        for _ in 0..coop_count {
-            self.add_bugster(
-                context,
-                self.scene,
-                PersonalityType::Cooperative,
-                random_range(-MAX_X..=MAX_X),
-                random_range(-MAX_Y..=MAX_Y),
-            );
-            //add the health of the bugster to the counter
-            self.change_coop_hp(BASE_HEALTH);
-            context
-                .user_interfaces
-                .first()
-                .send_message(TextMessage::text(
-                    self.coop_counter,
-                    MessageDirection::ToWidget,
-                    format!("Coop Total: {}", self.coop_hp).to_owned(),
-                ));
-        }
-        for _ in 0..greed_count {
-            self.add_bugster(
-                context,
-                self.scene,
-                PersonalityType::Greedy,
-                random_range(-MAX_X..=MAX_X),
-                random_range(-MAX_Y..=MAX_Y),
+        for &(strategy, count) in &spawn_counts {
+            for _ in 0..count {
+                let x = self.simulation.rng.random_range(-MAX_X..=MAX_X);
+                let y = self.simulation.rng.random_range(-MAX_Y..=MAX_Y);
+                self.add_bugster(
+                    context,
+                    self.scene,
+                    Birth::founder(strategy, BASE_HEALTH),
+                    x,
+                    y,
+                );
+            }
+        }
+        self.spawn_counts = spawn_counts;
+        //the starting population is the first sample
+        self.simulation.finish_tick();
+        self.start_recording(context);
+        let ui = context.user_interfaces.first_mut();
+        self.chart.clear(ui);
+        self.chart.update(ui, &self.simulation.stats);
+        ui.send_message(TextMessage::text(
+            self.seed_text,
+            MessageDirection::ToWidget,
+            format!("Seed: {seed}"),
+        ));
+        self.update_game_text(ui, &payoffs, !self.interaction.is_pairwise());
+        ui.send_message(TextMessage::text(
+            self.tick_text,
+            MessageDirection::ToWidget,
+            "Tick: 0".to_owned(),
+        ));
+        self.update_counters(ui);
+        self.update_speed_text(ui);
+        self.running = true;
+    }
+
+    //ends the current run, removing every bugster from the scene, what was recorded so far is
+    //still exported
+    fn teardown(&mut self, context: &mut PluginContext) {
+        if self.running {
+            self.export_results();
+        }
+        if let Err(e) = self.simulation.events.flush() {
+            Log::err(format!("Could not write events: {e}"));
+        }
+        self.running = false;
+        self.ended = None;
+
+        if let Some(scene) = context.scenes.try_get_mut(self.scene) {
+            let graph = &mut scene.graph;
+            let bugsters: Vec<_> = graph
+                .pair_iter()
+                .filter(|(_, node)| node.try_get_script::<Bugsters>().is_some())
+                .map(|(handle, _)| handle)
+                .collect();
+            for handle in bugsters {
+                graph.remove_node(handle);
+            }
+            for zone in self.zone_nodes.drain(..) {
+                graph.remove_node(zone);
+            }
+        }
+
+        //an empty run so the counters and statistics read zero until the next start
+        self.simulation = Simulation::new(
+            self.simulation.seed,
+            self.simulation.population.payoffs,
+            self.strategies.clone(),
+        );
+        let ui = context.user_interfaces.first_mut();
+        self.chart.clear(ui);
+        self.summary.set_visible(ui, false);
+        self.update_counters(ui);
+    }
+
+    //stops the run and shows how it went
+    fn end_run(&mut self, context: &mut PluginContext, reason: EndReason) {
+        self.ended = Some(reason);
+        self.simulation.final_sample();
+        let summary = self.run_summary(Some(reason));
+        Log::info(format!("{summary}"));
+        let ui = context.user_interfaces.first_mut();
+        self.chart.update(ui, &self.simulation.stats);
+        self.summary.show(ui, &summary);
+    }
+
+    //starts the same run again with the same seed and spawn counts
+    fn reset(&mut self, context: &mut PluginContext) {
+        let seed = self.simulation.seed;
+        let spawn_counts = std::mem::take(&mut self.spawn_counts);
+        self.teardown(context);
+        self.game_start(context, seed, spawn_counts);
+    }
+
+    //tears the run down and shows the start menu again, filled in with the last run's inputs
+    fn back_to_menu(&mut self, context: &mut PluginContext) {
+        self.teardown(context);
+        let ui = context.user_interfaces.first();
+        for (strategy, field) in &self.spawn_fields {
+            let count = self
+                .spawn_counts
+                .iter()
+                .find(|(s, _)| s == strategy)
+                .map_or(0, |(_, count)| *count);
+            ui.send_message(NumericUpDownMessage::value(
+                *field,
+                MessageDirection::ToWidget,
+                count,
+            ));
+        }
+        ui.send_message(NumericUpDownMessage::value(
+            self.seed_field,
+            MessageDirection::ToWidget,
+            self.simulation.seed,
+        ));
+        self.set_menu_visible(ui, true);
+    }
+
+    //shows the start menu and hides the run's chart, or the other way around
+    fn set_menu_visible(&self, ui: &UserInterface, visible: bool) {
+        for widget in [self.start, self.spawn_scroll] {
+            ui.send_message(WidgetMessage::visibility(
+                widget,
+                MessageDirection::ToWidget,
+                visible,
+            ));
+        }
+        self.chart.set_visible(ui, !visible);
+    }
+
+    //runs one fixed step of the simulation, bugsters are processed in spawn order so the same
+    //seed always gives the same sequence
+    fn simulation_tick(&mut self, context: &mut PluginContext) {
+        let tick = self.simulation.clock.tick();
+        let Some(scene) = context.scenes.try_get_mut(self.scene) else {
+            return;
+        };
+        let graph = &mut scene.graph;
+
+        let mut bugsters: Vec<(AgentId, Handle<Node>)> = graph
+            .pair_iter()
+            .filter_map(|(handle, node)| {
+                node.try_get_script::<Bugsters>()
+                    .map(|bugster| (bugster.agent, handle))
+            })
+            .collect();
+        bugsters.sort_unstable_by_key(|(agent, _)| *agent);
+
+        for (_, handle) in &bugsters {
+            let Some(bugster) = graph.try_get_script_of_mut::<Bugsters>(*handle) else {
+                continue;
+            };
+            let Some(impulse) = bugster.movement_tick(tick, &mut self.simulation.rng) else {
+                continue;
+            };
+            if let Some(rigid_body) = graph.try_get_mut_of_type::<RigidBody>(*handle) {
+                rigid_body.apply_impulse(impulse);
+            }
+        }
+
+        //gather every touching pair once, then let the simulation play them all at once
+        let mut nodes = HashMap::new();
+        let mut touching = Vec::new();
+        for (agent, handle) in &bugsters {
+            let Some(bugster) = graph.try_get_script_of::<Bugsters>(*handle) else {
+                continue;
+            };
+            nodes.insert(*agent, bugster.nodes);
+            for other in bugster.nodes.touching(graph) {
+                if let Some(other) = graph.try_get_script_of::<Bugsters>(other) {
+                    touching.push((*agent, other.agent));
+                }
+            }
+        }
+
+        let encounters = if self.interaction.is_pairwise() {
+            self.simulation.resolve(&touching)
+        } else {
+            Vec::new()
+        };
+        for encounter in &encounters {
+            let (Some(a), Some(b)) = (nodes.get(&encounter.a.agent), nodes.get(&encounter.b.agent))
+            else {
+                continue;
+            };
+            //knock the two away from eachother
+            a.bounce_from(graph, b);
+            b.bounce_from(graph, a);
+
+            for (side, side_nodes) in [(&encounter.a, a), (&encounter.b, b)] {
+                if side.died {
+                    graph.remove_node(side_nodes.rigid_body);
+                } else {
+                    side_nodes.change_size(graph, side.healthpoints);
+                }
+            }
+        }
+
+        //everyone standing in a zone plays the public goods game when one is due
+        let mut gatherings = Vec::new();
+        if let Some(public_goods) = self.interaction.public_goods() {
+            let positions: Vec<_> = bugsters
+                .iter()
+                .filter_map(|(agent, handle)| {
+                    let position = graph.try_get(*handle)?.global_position();
+                    Some((*agent, [position.x, position.y]))
+                })
+                .collect();
+            gatherings = self.simulation.gather(public_goods, &positions);
+        }
+        for side in gatherings.iter().flat_map(|gathering| &gathering.members) {
+            let Some(side_nodes) = nodes.get(&side.agent) else {
+                continue;
+            };
+            if side.died {
+                graph.remove_node(side_nodes.rigid_body);
+            } else {
+                side_nodes.change_size(graph, side.healthpoints);
+            }
+        }
+
+        //split every bugster that grew past the threshold, the offspring are added once the
+        //scene is free again
+        let mut births = Vec::new();
+        for parent in self.simulation.due_to_split(&self.reproduction) {
+            let Some(parent_nodes) = nodes.get(&parent) else {
+                continue;
+            };
+            let Some(position) = graph
+                .try_get(parent_nodes.rigid_body)
+                .map(|node| node.global_position())
+            else {
+                continue;
+            };
+            let Some(birth) = self.simulation.split(parent, &self.reproduction) else {
+                continue;
+            };
+            let parent_healthpoints = self
+                .simulation
+                .population
+                .get(parent)
+                .map_or(BASE_HEALTH, |a| a.healthpoints);
+            parent_nodes.change_size(graph, parent_healthpoints);
+            let [x, y] = arena::offspring_position(
+                [position.x, position.y],
+                parent_healthpoints,
+                birth.healthpoints,
+                &mut self.simulation.rng,
             );
-            self.change_greed_hp(BASE_HEALTH);
-            context
-                .user_interfaces
-                .first()
-                .send_message(TextMessage::text(
-                    self.greed_counter,
-                    MessageDirection::ToWidget,
-                    format!("Greed Total: {}", self.greed_hp).to_owned(),
-                ));
+            births.push((birth, x, y));
+        }
+
+        let mut positions = Vec::with_capacity(bugsters.len());
+        for (agent, handle) in &bugsters {
+            let position = graph.try_get(*handle).map(|node| node.global_position());
+            if let (Some(position), Some(bugster)) =
+                (position, graph.try_get_script_of_mut::<Bugsters>(*handle))
+            {
+                bugster.record_position(position.xy());
+                positions.push((*agent, [position.x, position.y]));
+            }
+        }
+        for (birth, x, y) in births {
+            if let Some(agent) = self.add_bugster(context, self.scene, birth, x, y) {
+                positions.push((agent, [x, y]));
+            }
+        }
+        self.recorder
+            .record_tick(&self.simulation, &encounters, &gatherings, &positions);
+    }
+
+    //starts a new replay recording from the current state of the arena
+    fn start_recording(&mut self, context: &mut PluginContext) {
+        self.recorder = ReplayRecorder::new(&self.simulation);
+        let Some(scene) = context.scenes.try_get(self.scene) else {
+            return;
+        };
+        //the bodies were only just placed, so their global transforms aren't updated yet
+        let positions: Vec<_> = scene
+            .graph
+            .pair_iter()
+            .filter_map(|(_, node)| {
+                let bugster = node.try_get_script::<Bugsters>()?;
+                let position = node.local_transform().position();
+                Some((bugster.agent, [position.x, position.y]))
+            })
+            .collect();
+        self.recorder
+            .record_tick(&self.simulation, &[], &[], &positions);
+    }
+
+    //opens a replay file and plays it back in place of the start menu
+    fn start_replay(&mut self, context: &mut PluginContext, path: &str) {
+        let replay = match Replay::load(path) {
+            Ok(replay) => replay,
+            Err(e) => {
+                Log::err(format!("Could not load replay {path}: {e}"));
+                return;
+            }
+        };
+        let names: Vec<_> = self
+            .strategies
+            .iter()
+            .map(|(_, s)| s.name().to_owned())
+            .collect();
+        if replay.strategies != names {
+            Log::err(format!(
+                "Replay {path} was recorded with different strategies: {}",
+                replay.strategies.join(", ")
+            ));
+            return;
+        }
+
+        Log::info(format!("Replaying seed {} from {path}", replay.seed));
+        let ui = context.user_interfaces.first();
+        for widget in [self.start, self.spawn_scroll] {
+            ui.send_message(WidgetMessage::visibility(
+                widget,
+                MessageDirection::ToWidget,
+                false,
+            ));
+        }
+        self.timeline.show(ui, replay.first_tick, replay.last_tick);
+        ui.send_message(TextMessage::text(
+            self.seed_text,
+            MessageDirection::ToWidget,
+            format!("Seed: {} (replay)", replay.seed),
+        ));
+        self.update_game_text(ui, &replay.payoffs, !replay.gatherings.is_empty());
+        self.replay_time = replay.first_tick as f32;
+        self.replay_playing = true;
+        self.replay = Some(ReplayPlayer::new(replay));
+    }
+
+    //plays or stops the replay, playing from the end starts it over
+    fn toggle_replay(&mut self) {
+        let Some(player) = &mut self.replay else {
+            return;
+        };
+        if !self.replay_playing && player.tick() >= player.replay().last_tick {
+            player.seek(player.replay().first_tick);
+            self.replay_time = player.tick() as f32;
+        }
+        self.replay_playing = !self.replay_playing;
+    }
+
+    //moves the replay to a tick, stopping playback
+    fn seek_replay(&mut self, tick: u64) {
+        if let Some(player) = &mut self.replay {
+            player.seek(tick);
+            self.replay_time = player.tick() as f32;
+            self.replay_playing = false;
+        }
+    }
+
+    //moves the replay along at the clock's speed and shows the arena at its current tick
+    fn replay_update(&mut self, context: &mut PluginContext) {
+        let speed = self.simulation.clock.speed();
+        let Some(player) = &mut self.replay else {
+            return;
+        };
+        if self.replay_playing {
+            let last_tick = player.replay().last_tick as f32;
+            self.replay_time =
+                (self.replay_time + context.dt * TICK_RATE as f32 * speed).min(last_tick);
+            self.replay_playing = self.replay_time < last_tick;
+            player.seek(self.replay_time as u64);
+            self.timeline
+                .set_tick(context.user_interfaces.first(), player.tick());
+        }
+        self.sync_replay(context);
+    }
+
+    //builds, moves, resizes and removes bugster nodes to match the replay's current tick
+    fn sync_replay(&mut self, context: &mut PluginContext) {
+        let Some(player) = &self.replay else {
+            return;
+        };
+        let tick = player.tick();
+        let alive: Vec<_> = player
+            .alive()
+            .filter_map(|(agent, _, hp)| {
+                Some((agent, player.lineage(agent)?, hp, player.position(agent)?))
+            })
+            .collect();
+
+        let gone: Vec<_> = self
+            .replay_nodes
+            .keys()
+            .filter(|agent| !alive.iter().any(|(a, ..)| a == *agent))
+            .copied()
+            .collect();
+        for agent in gone {
+            if let (Some((nodes, _)), Some(scene)) = (
+                self.replay_nodes.remove(&agent),
+                context.scenes.try_get_mut(self.scene),
+            ) {
+                scene.graph.remove_node(nodes.rigid_body);
+            }
+        }
+
+        for (agent, lineage, hp, [x, y]) in alive {
+            if !self.replay_nodes.contains_key(&agent) {
+                let Some(nodes) = self.build_bugster(context, self.scene, &lineage, x, y) else {
+                    continue;
+                };
+                self.replay_nodes.insert(agent, (nodes, BASE_HEALTH));
+            }
+            let (Some(scene), Some((nodes, shown_hp))) = (
+                context.scenes.try_get_mut(self.scene),
+                self.replay_nodes.get_mut(&agent),
+            ) else {
+                continue;
+            };
+            let graph = &mut scene.graph;
+            if let Some(body) = graph.try_get_mut(nodes.rigid_body) {
+                body.local_transform_mut()
+                    .set_position(Vector3::new(x, y, 0.0));
+            }
+            if *shown_hp != hp {
+                nodes.change_size(graph, hp);
+                *shown_hp = hp;
+            }
+        }
+
+        //the bodies are only placed, never simulated
+        self.hold_physics(context);
+        let ui = context.user_interfaces.first();
+        ui.send_message(TextMessage::text(
+            self.tick_text,
+            MessageDirection::ToWidget,
+            format!("Tick: {tick}"),
+        ));
+        self.update_counters(ui);
+    }
+
+    //pauses or resumes the run
+    fn toggle_pause(&mut self, ui: &UserInterface) {
+        let clock = &mut self.simulation.clock;
+        clock.set_paused(!clock.is_paused());
+        self.update_speed_text(ui);
+    }
+
+    //runs a single tick of a paused run
+    fn step(&mut self) {
+        if self.simulation.clock.is_paused() {
+            self.simulation.clock.step();
+        }
+    }
+
+    fn change_speed(&mut self, ui: &UserInterface, faster: bool) {
+        self.simulation.clock.change_speed(faster);
+        self.update_speed_text(ui);
+    }
+
+    //the payoffs of the game picked in the start menu, the prisoner's dilemma and custom games
+    //are reloaded from their files every run so experiments don't need a rebuild
+    fn load_payoffs(&self) -> PayoffMatrix {
+        let path = match self.game {
+            GameType::PrisonersDilemma => PAYOFF_PATH,
+            GameType::Custom => CUSTOM_PAYOFF_PATH,
+            game => return game.preset().unwrap_or_default(),
+        };
+        match PayoffMatrix::load_as(path, self.game) {
+            Ok(payoffs) => payoffs,
+            Err(e) => {
+                Log::err(format!("{e}, using the default payoffs"));
+                PayoffMatrix::default()
+            }
+        }
+    }
+
+    //reads from the start menu whether bugsters play in pairs on contact or in the public goods
+    //zones, with the stake and the pot multiplier as a percentage
+    fn read_interaction(&self, ui: &mut UserInterface) -> Interaction {
+        let public_goods = ui
+            .try_get_mut_of_type::<CheckBox>(self.public_goods_check)
+            .is_some_and(|check_box| *check_box.checked == Some(true));
+        if !public_goods {
+            return Interaction::Pairwise;
+        }
+        let mut number = |field| {
+            ui.try_get_mut_of_type::<NumericUpDown<u64>>(field)
+                .map(|field| *field.value)
+        };
+        let defaults = PublicGoods::default();
+        Interaction::PublicGoods(PublicGoods {
+            stake: number(self.stake_field).map_or(defaults.stake, |stake| stake as i64),
+            multiplier: number(self.multiplier_field)
+                .map_or(defaults.multiplier, |percent| percent as f64 / 100.0),
+            ..defaults
+        })
+    }
+
+    //marks every public goods zone of the run with a faint quad behind the bugsters
+    fn build_zones(&mut self, context: &mut PluginContext) {
+        let Some(public_goods) = self.interaction.public_goods() else {
+            return;
+        };
+        let Some(scene) = context.scenes.try_get_mut(self.scene) else {
+            return;
+        };
+        for zone in &public_goods.zones {
+            let quad = RectangleBuilder::new(
+                BaseBuilder::new().with_local_transform(
+                    TransformBuilder::new()
+                        .with_local_scale(Vector3::new(
+                            zone.half_size[0] * 2.0,
+                            zone.half_size[1] * 2.0,
+                            1.0,
+                        ))
+                        .with_local_position(Vector3::new(zone.center[0], zone.center[1], 2.0))
+                        .build(),
+                ),
+            )
+            .with_color(Color::from_rgba(80, 160, 255, 60))
+            .build(&mut scene.graph);
+            self.zone_nodes.push(quad);
+        }
+    }
+
+    //the summary of the run so far, naming the public goods game when that was played
+    fn run_summary(&self, reason: Option<EndReason>) -> RunSummary {
+        let mut summary = RunSummary::new(&self.simulation, reason);
+        summary.public_goods = self.interaction.public_goods().cloned();
+        summary
+    }
+
+    //picks the game the next run plays and shows it in the start menu
+    fn set_game(&mut self, ui: &UserInterface, game: GameType) {
+        self.game = game;
+        ui.send_message(TextMessage::text(
+            self.game_label,
+            MessageDirection::ToWidget,
+            game.to_string(),
+        ));
+    }
+
+    //shows the game on screen, public goods or the game the payoffs make
+    fn update_game_text(&self, ui: &UserInterface, payoffs: &PayoffMatrix, public_goods: bool) {
+        let game = if public_goods {
+            "Public Goods".to_owned()
+        } else {
+            GameType::of(payoffs).to_string()
+        };
+        ui.send_message(TextMessage::text(
+            self.game_text,
+            MessageDirection::ToWidget,
+            format!("Game: {game}"),
+        ));
+    }
+
+    fn update_speed_text(&self, ui: &UserInterface) {
+        let clock = &self.simulation.clock;
+        let text = if clock.is_paused() {
+            format!("Speed: {}x (paused)", clock.speed())
+        } else {
+            format!("Speed: {}x", clock.speed())
+        };
+        ui.send_message(TextMessage::text(
+            self.speed_text,
+            MessageDirection::ToWidget,
+            text,
+        ));
+    }
+
+    //keeps the engine from stepping the physics on its own with the frame time, bodies only
+    //move in `step_physics`
+    fn hold_physics(&self, context: &mut PluginContext) {
+        if let Some(scene) = context.scenes.try_get_mut(self.scene) {
+            scene
+                .graph
+                .physics2d
+                .enabled
+                .set_value_and_mark_modified(false);
+        }
+    }
+
+    //advances the physics by exactly one tick, so every tick reads the contacts of its own
+    //step and a run plays out the same at any frame rate or speed
+    fn step_physics(&self, context: &mut PluginContext) {
+        let Some(scene) = context.scenes.try_get_mut(self.scene) else {
+            return;
+        };
+        let graph = &mut scene.graph;
+        graph.physics2d.enabled.set_value_and_mark_modified(true);
+        graph.physics2d.integration_parameters.dt = Some(TICK_DT);
+        //no node overrides to update, only the bodies are synced and stepped
+        graph.update(
+            Vector2::repeat(1.0),
+            TICK_DT,
+            GraphUpdateSwitches {
+                physics2d: true,
+                physics: false,
+                node_overrides: Some(Default::default()),
+                ..Default::default()
+            },
+        );
+        graph.physics2d.enabled.set_value_and_mark_modified(false);
+    }
+
+    //writes the time series, summary, replay and lineage of the run so far to the results
+    //directory
+    fn export_results(&self) {
+        let dir = arg_value("--output").map_or_else(|| PathBuf::from(RESULTS_DIR), PathBuf::from);
+        let name = |kind: &str, extension: &str| {
+            dir.join(format!(
+                "{kind}_seed{}_tick{}.{extension}",
+                self.simulation.seed,
+                self.simulation.clock.tick()
+            ))
+        };
+        let timeseries = name("timeseries", "csv");
+        let summary = name("summary", "json");
+        let replay = name("replay", "json");
+        let lineage_tree = name("lineage", "nwk");
+        let lineage_edges = name("lineage", "csv");
+        let lineage = &self.simulation.lineage;
+        let result = std::fs::create_dir_all(&dir)
+            .and_then(|_| {
+                self.simulation.stats.export_csv(
+                    &timeseries,
+                    self.simulation.seed,
+                    &self.strategies,
+                )
+            })
+            .and_then(|_| self.run_summary(self.ended).export_json(&summary))
+            .and_then(|_| self.recorder.replay().save(&replay))
+            .and_then(|_| lineage.export_newick(&lineage_tree, &self.strategies))
+            .and_then(|_| {
+                lineage.export_csv(&lineage_edges, self.simulation.seed, &self.strategies)
+            });
+        match result {
+            Ok(()) => Log::info(format!(
+                "Wrote {}, {}, {}, {} and {}",
+                timeseries.display(),
+                summary.display(),
+                replay.display(),
+                lineage_tree.display(),
+                lineage_edges.display()
+            )),
+            Err(e) => Log::err(format!("Could not write results to {}: {e}", dir.display())),
+        }
+    }
+
+    //reads the end conditions from the start menu
+    fn read_end_conditions(&self, ui: &mut UserInterface) -> EndConditions {
+        let mut number = |field| {
+            ui.try_get_mut_of_type::<NumericUpDown<u64>>(field)
+                .map(|field| *field.value)
+                .filter(|value| *value > 0)
+        };
+        let tick_limit = number(self.tick_limit_field);
+        let stable_for = number(self.stable_field);
+        let mut checked = |check_box| {
+            ui.try_get_mut_of_type::<CheckBox>(check_box)
+                .is_some_and(|check_box| *check_box.checked == Some(true))
+        };
+        EndConditions {
+            one_strategy_left: checked(self.one_left_check),
+            all_dead: checked(self.all_dead_check),
+            tick_limit,
+            stable_for,
+        }
+    }
+
+    //reads when bugsters split and how often offspring and their genomes mutate from the start
+    //menu, 0 turns any of them off
+    fn read_reproduction(&self, ui: &mut UserInterface) -> Reproduction {
+        let mut number = |field| {
+            ui.try_get_mut_of_type::<NumericUpDown<u64>>(field)
+                .map(|field| *field.value)
+                .filter(|value| *value > 0)
+        };
+        let threshold = number(self.split_field).map(|hp| hp as i64);
+        let capacity = number(self.capacity_field).map(|count| count as usize);
+        let mut chance =
+            |field| number(field).map_or(0.0, |percent| percent.min(100) as f64 / 100.0);
+        let reproduction = Reproduction {
+            threshold,
+            capacity,
+            mutation: chance(self.mutation_field),
+            crossover: chance(self.crossover_field),
+            point_mutation: chance(self.point_mutation_field),
+        };
+        match reproduction.validate() {
+            Ok(()) => reproduction,
+            Err(e) => {
+                Log::err(format!("{e}, bugsters won't split"));
+                Reproduction {
+                    threshold: None,
+                    ..reproduction
+                }
+            }
+        }
+    }
+
+    //reads how often bugsters play or remember the wrong move from the start menu
+    fn read_noise(&self, ui: &mut UserInterface) -> Noise {
+        let mut chance = |field| {
+            ui.try_get_mut_of_type::<NumericUpDown<u64>>(field)
+                .map_or(0.0, |field| (*field.value).min(100) as f64 / 100.0)
+        };
+        Noise {
+            execution: chance(self.execution_field),
+            perception: chance(self.perception_field),
         }
     }
 
-    pub fn change_coop_hp(&mut self, value: i64) {
-        self.coop_hp += value
+    //the health total of a strategy in the run or replay on screen
+    fn total_hp(&self, strategy: StrategyId) -> i64 {
+        match &self.replay {
+            Some(player) => player.total_hp(strategy),
+            None => self.simulation.population.total_hp(strategy),
+        }
     }
-    pub fn change_greed_hp(&mut self, value: i64) {
-        self.greed_hp += value
+
+    //sends the current health totals of the population to the hud
+    pub fn update_counters(&self, ui: &UserInterface) {
+        for (id, counter) in &self.counters {
+            let Some(strategy) = self.strategies.get(*id) else {
+                continue;
+            };
+            ui.send_message(TextMessage::text(
+                *counter,
+                MessageDirection::ToWidget,
+                format!("{} Total: {}", strategy.name(), self.total_hp(*id)),
+            ));
+        }
+    }
+
+    //finds the counter and spawn field of every registered strategy, start.ui has the ones
+    //named "<name>Count" and "<name>", anything missing is built here
+    fn build_strategy_widgets(&mut self, ui: &mut UserInterface) {
+        let screen = ui.find_handle_by_name_from_root("Screen");
+        (self.spawn_scroll, self.spawn_panel) = hud::build_spawn_panel(ui, screen);
+        self.counters.clear();
+        self.spawn_fields.clear();
+
+        //built counters go in the same panel as the ones from start.ui
+        let counter_name = |strategy: &dyn Strategy| format!("{}Count", strategy.name());
+        let counter_panel = self
+            .strategies
+            .iter()
+            .map(|(_, strategy)| ui.find_handle_by_name_from_root(&counter_name(strategy)))
+            .find(|counter| counter.is_some())
+            .and_then(|counter| ui.try_get(counter))
+            .map(|counter| counter.parent())
+            .unwrap_or(screen);
+
+        for (id, strategy) in self.strategies.iter() {
+            let mut counter = ui.find_handle_by_name_from_root(&counter_name(strategy));
+            if counter.is_none() {
+                counter = hud::build_counter(ui, strategy, counter_panel);
+            }
+            self.counters.push((id, counter));
+
+            let mut field = ui.find_handle_by_name_from_root(strategy.name());
+            if field.is_none() {
+                field = hud::build_spawn_field(ui, strategy, self.spawn_panel);
+            }
+            self.spawn_fields.push((id, field));
+        }
+
+        //the seed field starts with the one given on the command line, or a fresh one
+        let seed = seed_from_args().unwrap_or_else(rng::random_seed);
+        self.seed_field = hud::build_number_field(ui, "Seed", seed, self.spawn_panel);
+        //a limit of 0 means no limit
+        self.tick_limit_field = hud::build_number_field(ui, "Tick limit", 0, self.spawn_panel);
+        self.stable_field = hud::build_number_field(ui, "Stable ticks", 0, self.spawn_panel);
+        self.one_left_check =
+            hud::build_check_box(ui, "End when one strategy is left", true, self.spawn_panel);
+        self.all_dead_check =
+            hud::build_check_box(ui, "End when all are dead", true, self.spawn_panel);
+        self.split_field = hud::build_number_field(
+            ui,
+            "Split above HP",
+            DEFAULT_SPLIT_THRESHOLD,
+            self.spawn_panel,
+        );
+        self.capacity_field = hud::build_number_field(
+            ui,
+            "Max bugsters",
+            DEFAULT_CAPACITY as u64,
+            self.spawn_panel,
+        );
+        self.mutation_field = hud::build_number_field(ui, "Mutation chance %", 0, self.spawn_panel);
+        self.crossover_field = hud::build_number_field(ui, "Crossover %", 0, self.spawn_panel);
+        self.point_mutation_field =
+            hud::build_number_field(ui, "Gene mutation %", 0, self.spawn_panel);
+        self.execution_field =
+            hud::build_number_field(ui, "Execution noise %", 0, self.spawn_panel);
+        self.perception_field =
+            hud::build_number_field(ui, "Perception noise %", 0, self.spawn_panel);
+        let public_goods = PublicGoods::default();
+        self.public_goods_check =
+            hud::build_check_box(ui, "Public goods in zones", false, self.spawn_panel);
+        self.stake_field =
+            hud::build_number_field(ui, "Stake HP", public_goods.stake as u64, self.spawn_panel);
+        self.multiplier_field = hud::build_number_field(
+            ui,
+            "Pot multiplier %",
+            (public_goods.multiplier * 100.0) as u64,
+            self.spawn_panel,
+        );
+        (self.game_button, self.game_label) =
+            hud::build_choice(ui, "Game", &self.game.to_string(), self.spawn_panel);
+        self.seed_text = hud::build_hud_text(ui, Color::WHITE, "Seed: -", counter_panel);
+        self.game_text = hud::build_hud_text(ui, Color::WHITE, "Game: -", counter_panel);
+        self.tick_text = hud::build_hud_text(ui, Color::WHITE, "Tick: 0", counter_panel);
+        self.speed_text = hud::build_hud_text(ui, Color::WHITE, "Speed: 1x", counter_panel);
+        let controls = hud::build_button_row(ui, counter_panel);
+        self.pause = hud::build_button(ui, "Pause", controls);
+        self.step = hud::build_button(ui, "Step", controls);
+        self.slower = hud::build_button(ui, "Slower", controls);
+        self.faster = hud::build_button(ui, "Faster", controls);
+        let run_controls = hud::build_button_row(ui, counter_panel);
+        self.reset = hud::build_button(ui, "Reset", run_controls);
+        self.menu = hud::build_button(ui, "Menu", run_controls);
+        self.export = hud::build_button(ui, "Export", run_controls);
+        let snapshot_controls = hud::build_button_row(ui, counter_panel);
+        self.save = hud::build_button(ui, "Save", snapshot_controls);
+        self.load = hud::build_button(ui, "Load", snapshot_controls);
+        self.chart = PopulationChart::build(ui, &self.strategies, screen);
+        self.summary = SummaryScreen::build(ui, screen);
+        self.timeline = Timeline::build(ui, screen);
     }
 
-    //creates the bugster at a given position
+    //creates the bugster at a given position, either a founder or the offspring of a split
     pub fn add_bugster(
         &mut self,
         context: &mut PluginContext,
         scene_handle: Handle<Scene>,
-        personality: PersonalityType,
+        birth: Birth,
         x: f32,
         y: f32,
-    ) {
+    ) -> Option<AgentId> {
+        let strategy = birth.strategy;
+        if self.strategies.get(strategy).is_none() {
+            Log::err(format!("No strategy registered with id {strategy}"));
+            return None;
+        }
+        let agent = self.simulation.birth(birth);
+        let lineage = *self.simulation.lineage.get(agent)?;
+        let nodes = self.build_bugster(context, scene_handle, &lineage, x, y)?;
+        if birth.healthpoints != BASE_HEALTH {
+            if let Some(scene) = context.scenes.try_get_mut(scene_handle) {
+                nodes.change_size(&mut scene.graph, birth.healthpoints);
+            }
+        }
+        Some(agent)
+    }
+
+    //builds the scene nodes of an agent that is already in the simulation
+    fn build_bugster(
+        &self,
+        context: &mut PluginContext,
+        scene_handle: Handle<Scene>,
+        lineage: &LineageNode,
+        x: f32,
+        y: f32,
+    ) -> Option<BugsterNodes> {
+        let strategy_info = self.strategies.get(lineage.strategy)?;
         let scene = context
             .scenes
             .try_get_mut(scene_handle)
@@ -154,13 +1133,15 @@ impl Game {
             .with_sensor(true)
             .build(graph);
 
-        let sprite = self.get_texture(&personality, graph, context.resource_manager);
+        let sprite = Self::get_texture(strategy_info, graph, context.resource_manager);
+        let (health_bar, health_fill) = BugsterNodes::build_health_bar(graph);
 
         //create our rigid body and attach our colliders
         let node_handle = RigidBodyBuilder::new(BaseBuilder::new().with_children(&[
             collision_body,
             detector_body,
             sprite,
+            health_bar,
         ]))
         .with_mass(1.0)
         .with_lin_vel(Vector2::new(0.0, 0.0))
@@ -173,43 +1154,166 @@ impl Game {
 
         //then attach the script to it
 
+        let nodes = BugsterNodes {
+            rigid_body: node_handle,
+            collision: collision_body,
+            detector: detector_body,
+            sprite,
+            health_bar,
+            health_fill,
+        };
         if let Some(node) = graph.try_get_mut(node_handle) {
-            node.add_script(Bugsters::new(
-                BASE_HEALTH,
-                personality,
-                node_handle,
-                collision_body,
-                detector_body,
-            ));
+            node.add_script(Bugsters::new(lineage, nodes, Vector2::new(x, y)));
             node.local_transform_mut()
                 .set_position(Vector3::new(x, y, 0.0));
         }
+        nodes.update_health_bar(graph, BASE_HEALTH, 1.0);
+        Some(nodes)
+    }
+
+    fn snapshot_path() -> String {
+        arg_value("--snapshot").unwrap_or_else(|| SNAPSHOT_PATH.to_owned())
+    }
+
+    //writes the whole run to the snapshot file
+    fn save_snapshot(&self, context: &mut PluginContext) {
+        let Some(scene) = context.scenes.try_get(self.scene) else {
+            return;
+        };
+        let graph = &scene.graph;
+        let mut bugsters: Vec<_> = graph
+            .pair_iter()
+            .filter_map(|(_, node)| node.try_get_script::<Bugsters>())
+            .filter_map(|bugster| bugster.save(graph))
+            .collect();
+        bugsters.sort_unstable_by_key(|bugster| bugster.agent);
+
+        let snapshot = Snapshot {
+            simulation: self.simulation.save_state(),
+            arena: ArenaState {
+                bugsters,
+                spawn_counts: self.spawn_counts.clone(),
+                end_conditions: self.end_conditions,
+                ended: self.ended,
+                reproduction: self.reproduction,
+                interaction: self.interaction.clone(),
+            },
+        };
+        let path = Self::snapshot_path();
+        if let Some(dir) = Path::new(&path).parent() {
+            Log::verify(std::fs::create_dir_all(dir));
+        }
+        match snapshot.save(&path) {
+            Ok(()) => Log::info(format!(
+                "Saved tick {} to {path}",
+                self.simulation.clock.tick()
+            )),
+            Err(e) => Log::err(format!("Could not save to {path}: {e}")),
+        }
+    }
+
+    //replaces whatever is running with the run saved in the snapshot file
+    fn load_snapshot(&mut self, context: &mut PluginContext) {
+        let path = Self::snapshot_path();
+        let loaded = Snapshot::<ArenaState>::load(&path).and_then(|snapshot| {
+            Simulation::from_state(snapshot.simulation, self.strategies.clone())
+                .map(|simulation| (simulation, snapshot.arena))
+        });
+        let (simulation, arena) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                Log::err(format!("Could not load {path}: {e}"));
+                return;
+            }
+        };
+
+        self.teardown(context);
+        self.simulation = simulation;
+        self.spawn_counts = arena.spawn_counts;
+        self.end_conditions = arena.end_conditions;
+        self.reproduction = arena.reproduction;
+        self.interaction = arena.interaction;
+        self.noise = self.simulation.population.noise;
+        self.build_zones(context);
+        //the restored events are written to the stream again, so it holds the whole run
+        if let Some(path) = arg_value("--events") {
+            if let Err(e) = self.simulation.events.stream_to(&path) {
+                Log::err(format!("Could not write events to {path}: {e}"));
+            }
+        }
+
+        for state in &arena.bugsters {
+            let Some(agent) = self.simulation.population.get(state.agent) else {
+                continue;
+            };
+            let healthpoints = agent.healthpoints;
+            let lineage = LineageNode::of(agent);
+            let [x, y] = state.position;
+            let Some(nodes) = self.build_bugster(context, self.scene, &lineage, x, y) else {
+                continue;
+            };
+            let Some(scene) = context.scenes.try_get_mut(self.scene) else {
+                continue;
+            };
+            let graph = &mut scene.graph;
+            nodes.set_size(graph, state.scale, state.half_extent);
+            nodes.update_health_bar(graph, healthpoints, state.scale);
+            if let Some(rigid_body) = graph.try_get_mut_of_type::<RigidBody>(nodes.rigid_body) {
+                rigid_body.set_lin_vel(state.velocity.into());
+            }
+            if let Some(bugster) = graph.try_get_script_of_mut::<Bugsters>(nodes.rigid_body) {
+                bugster.restore(state);
+            }
+        }
+
+        self.start_recording(context);
+        self.running = true;
+        self.ended = arena.ended;
+        let ui = context.user_interfaces.first_mut();
+        self.set_menu_visible(ui, false);
+        self.chart.update(ui, &self.simulation.stats);
+        if let Some(reason) = self.ended {
+            self.summary.show(ui, &self.run_summary(Some(reason)));
+        }
+        ui.send_message(TextMessage::text(
+            self.seed_text,
+            MessageDirection::ToWidget,
+            format!("Seed: {}", self.simulation.seed),
+        ));
+        let payoffs = self.simulation.population.payoffs;
+        self.update_game_text(ui, &payoffs, !self.interaction.is_pairwise());
+        self.set_game(ui, GameType::of(&payoffs));
+        ui.send_message(TextMessage::text(
+            self.tick_text,
+            MessageDirection::ToWidget,
+            format!("Tick: {}", self.simulation.clock.tick()),
+        ));
+        self.update_counters(ui);
+        self.update_speed_text(ui);
+        Log::info(format!(
+            "Loaded tick {} of seed {} from {path}",
+            self.simulation.clock.tick(),
+            self.simulation.seed
+        ));
     }
 
-    //gets the texture of the bugster based on its personality type
+    //gets the texture of the bugster based on its strategy
     fn get_texture(
-        &mut self,
-        personality: &PersonalityType,
+        strategy: &dyn Strategy,
         graph: &mut Graph,
         resource_manager: &ResourceManager,
     ) -> Handle<Node> {
         let mut material = Material::standard_2d();
-        match personality {
-            PersonalityType::Cooperative => {
-                material.bind(
-                    "diffuseTexture",
-                    Some(resource_manager.request::<Texture>(COOPERATIVE_SPRITE_PATH)),
-                );
-                Log::info("Set sprite to cooperative texture");
-            }
-            PersonalityType::Greedy => {
-                material.bind(
-                    "diffuseTexture",
-                    Some(resource_manager.request::<Texture>(GREEDY_SPRITE_PATH)),
-                );
-                Log::info("Set sprite to greedy texture");
-            }
-        }
+        //strategies with their own sprite use it as is, the rest tint the base sprite
+        let (sprite_path, tint) = match strategy.sprite() {
+            Some(path) => (path, Color::WHITE),
+            None => (BASE_SPRITE_PATH, hud::strategy_color(strategy)),
+        };
+        material.bind(
+            "diffuseTexture",
+            Some(resource_manager.request::<Texture>(sprite_path)),
+        );
+        Log::info(format!("Set sprite to {} texture", strategy.name()));
 
         let material_resource = MaterialResource::new_ok(
             uuid::Uuid::new_v4(), // Generate a random UUID for the resource
@@ -227,10 +1331,28 @@ impl Game {
             ),
         )
         .with_material(material_resource)
+        .with_color(tint)
         .build(graph)
     }
 }
 
+//reads the value that follows a flag such as `--seed <n>` on the command line
+fn arg_value(flag: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != flag).nth(1)
+}
+
+//reads `--seed <n>` from the command line
+fn seed_from_args() -> Option<u64> {
+    let seed = arg_value("--seed")?;
+    match seed.parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => {
+            Log::err(format!("Invalid seed {seed}, expected a whole number"));
+            None
+        }
+    }
+}
+
 impl Plugin for Game {
     fn register(&self, context: PluginRegistrationContext) {
         context
@@ -240,6 +1362,31 @@ impl Plugin for Game {
     }
 
     fn init(&mut self, scene_path: Option<&str>, context: PluginContext) {
+        //`--genomes <file>` adds the genome strategies saved by `pd-sim evolve` to the menu and
+        //`--mixed <file>` strategies that cooperate with a set chance
+        let mut strategies = StrategyRegistry::default();
+        if let Some(path) = arg_value("--genomes") {
+            match Evolved::load_all(&path) {
+                Ok(genomes) => {
+                    for genome in genomes {
+                        strategies.register(genome);
+                    }
+                }
+                Err(e) => Log::err(format!("Could not load genomes from {path}: {e}")),
+            }
+        }
+        if let Some(path) = arg_value("--mixed") {
+            match Mixed::load_all(&path) {
+                Ok(mixed) => {
+                    for strategy in mixed {
+                        strategies.register(strategy);
+                    }
+                }
+                Err(e) => Log::err(format!("Could not load mixed strategies from {path}: {e}")),
+            }
+        }
+        self.strategies = Arc::new(strategies);
+
         //loads the main scene
         context
             .async_scene_loader
@@ -261,26 +1408,87 @@ impl Plugin for Game {
                     .user_interfaces
                     .first()
                     .find_handle_by_name_from_root("Exit");
-                game.coop_field = ctx
-                    .user_interfaces
-                    .first()
-                    .find_handle_by_name_from_root("Coop");
-                game.greed_field = ctx
-                    .user_interfaces
-                    .first()
-                    .find_handle_by_name_from_root("Greed");
-                game.coop_counter = ctx
-                    .user_interfaces
-                    .first()
-                    .find_handle_by_name_from_root("CoopCount");
-                game.greed_counter = ctx
-                    .user_interfaces
-                    .first()
-                    .find_handle_by_name_from_root("GreedyCount");
+                game.build_strategy_widgets(ctx.user_interfaces.first_mut());
+                //`--replay <file>` plays a recorded run back instead of showing the menu
+                if let Some(path) = arg_value("--replay") {
+                    game.start_replay(ctx, &path);
+                }
             },
         );
     }
 
+    fn update(&mut self, context: &mut PluginContext) {
+        self.hold_physics(context);
+        if self.replay.is_some() {
+            self.replay_update(context);
+            return;
+        }
+        if !self.running || self.ended.is_some() {
+            return;
+        }
+
+        //spend the frame time in fixed ticks so results don't depend on the frame rate, each
+        //tick plays the contacts of the last physics step and then steps it once
+        self.simulation.clock.accumulate(context.dt);
+        let mut ticks = 0;
+        while self.simulation.clock.next_tick() {
+            self.simulation_tick(context);
+            self.step_physics(context);
+            self.simulation.finish_tick();
+            ticks += 1;
+            if let Some(reason) = self.end_conditions.check(&self.simulation) {
+                self.end_run(context, reason);
+                break;
+            }
+        }
+
+        if let Some(e) = self.simulation.events.take_error() {
+            Log::err(format!("Stopped writing events: {e}"));
+        }
+
+        if ticks > 0 {
+            let ui = context.user_interfaces.first_mut();
+            self.chart.update(ui, &self.simulation.stats);
+            ui.send_message(TextMessage::text(
+                self.tick_text,
+                MessageDirection::ToWidget,
+                format!("Tick: {}", self.simulation.clock.tick()),
+            ));
+            self.update_counters(ui);
+        }
+    }
+
+    //space pauses, period steps a paused run, minus and equals change the speed
+    fn on_os_event(&mut self, event: &Event<()>, context: PluginContext) {
+        let Event::WindowEvent {
+            event: WindowEvent::KeyboardInput { event: input, .. },
+            ..
+        } = event
+        else {
+            return;
+        };
+        if input.state != ElementState::Pressed {
+            return;
+        }
+        if self.replay.is_some() {
+            if input.physical_key == PhysicalKey::Code(KeyCode::Space) {
+                self.toggle_replay();
+            }
+            return;
+        }
+        if !self.running {
+            return;
+        }
+        let ui = context.user_interfaces.first();
+        match input.physical_key {
+            PhysicalKey::Code(KeyCode::Space) => self.toggle_pause(ui),
+            PhysicalKey::Code(KeyCode::Period) => self.step(),
+            PhysicalKey::Code(KeyCode::Minus) => self.change_speed(ui, false),
+            PhysicalKey::Code(KeyCode::Equal) => self.change_speed(ui, true),
+            _ => {}
+        }
+    }
+
     fn on_graphics_context_initialized(&mut self, context: PluginContext) {
         let graphics_context = context.graphics_context.as_initialized_mut();
         let mut settings = QualitySettings::low();
@@ -297,31 +1505,90 @@ impl Plugin for Game {
         message: &fyrox::gui::message::UiMessage,
         _ui_handle: Handle<UserInterface>,
     ) {
+        if let Some(ScrollBarMessage::Value(tick)) = message.data() {
+            if message.destination() == self.timeline.slider
+                && message.direction() == MessageDirection::FromWidget
+                && (*tick as u64) != self.replay.as_ref().map_or(0, |p| p.tick())
+            {
+                self.seek_replay(*tick as u64);
+            }
+        }
         if let Some(ButtonMessage::Click) = message.data() {
-            if message.destination() == self.start {
-                let coop_count = *context
-                    .user_interfaces
-                    .first_mut()
-                    .try_get_mut_of_type::<NumericUpDown<i64>>(self.coop_field)
-                    .unwrap()
-                    .value;
-                let greed_count = *context
-                    .user_interfaces
-                    .first_mut()
-                    .try_get_mut_of_type::<NumericUpDown<i64>>(self.greed_field)
-                    .unwrap()
-                    .value;
+            if message.destination() == self.timeline.play {
+                self.toggle_replay();
+            } else if message.destination() == self.timeline.rewind {
+                let first_tick = self.replay.as_ref().map(|p| p.replay().first_tick);
+                if let Some(first_tick) = first_tick {
+                    self.seek_replay(first_tick);
+                    self.timeline
+                        .set_tick(context.user_interfaces.first(), first_tick);
+                }
+            } else if message.destination() == self.start {
+                let ui = context.user_interfaces.first_mut();
+                let spawn_counts = self
+                    .spawn_fields
+                    .iter()
+                    .map(|(strategy, field)| {
+                        let count = ui
+                            .try_get_mut_of_type::<NumericUpDown<i64>>(*field)
+                            .map(|field| *field.value)
+                            .unwrap_or_default();
+                        (*strategy, count)
+                    })
+                    .collect();
+                let seed = ui
+                    .try_get_mut_of_type::<NumericUpDown<u64>>(self.seed_field)
+                    .map(|field| *field.value)
+                    .unwrap_or_else(rng::random_seed);
+                self.end_conditions = self.read_end_conditions(ui);
+                self.reproduction = self.read_reproduction(ui);
+                self.noise = self.read_noise(ui);
+                self.interaction = self.read_interaction(ui);
 
-                self.game_start(context, coop_count, greed_count);
-                context
-                    .user_interfaces
-                    .first()
-                    .send_message(WidgetMessage::visibility(
-                        self.start,
-                        MessageDirection::ToWidget,
-                        false,
-                    ));
+                self.game_start(context, seed, spawn_counts);
+                self.set_menu_visible(context.user_interfaces.first(), false);
+            } else if message.destination() == self.game_button {
+                self.set_game(context.user_interfaces.first(), self.game.next());
+            } else if message.destination() == self.save {
+                if self.running {
+                    self.save_snapshot(context);
+                }
+            } else if message.destination() == self.load {
+                self.load_snapshot(context);
+            } else if message.destination() == self.summary.export {
+                self.export_results();
+            } else if message.destination() == self.reset
+                || message.destination() == self.summary.restart
+            {
+                if self.running {
+                    self.reset(context);
+                }
+            } else if message.destination() == self.menu
+                || message.destination() == self.summary.menu
+            {
+                if self.running {
+                    self.back_to_menu(context);
+                }
+            } else if message.destination() == self.pause {
+                self.toggle_pause(context.user_interfaces.first());
+            } else if message.destination() == self.step {
+                self.step();
+            } else if message.destination() == self.slower {
+                self.change_speed(context.user_interfaces.first(), false);
+            } else if message.destination() == self.faster {
+                self.change_speed(context.user_interfaces.first(), true);
+            } else if message.destination() == self.chart.toggle {
+                self.chart
+                    .toggle_metric(context.user_interfaces.first_mut(), &self.simulation.stats);
+            } else if message.destination() == self.export {
+                if self.running {
+                    self.export_results();
+                }
             } else if message.destination() == self.exit {
+                //leaving mid run still keeps what was recorded
+                if self.running {
+                    self.export_results();
+                }
                 context.loop_controller.exit();
             }
         }
@@ -0,0 +1,79 @@
+use fyrox::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        message::MessageDirection,
+        stack_panel::StackPanelBuilder,
+        text::{TextBuilder, TextMessage},
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+use pd_core::RunSummary;
+
+use crate::hud;
+
+const SUMMARY_FONT_SIZE: f32 = 20.0;
+
+//the screen shown over the arena once a run ends
+#[derive(Debug, Default)]
+pub struct SummaryScreen {
+    pub panel: Handle<UiNode>,
+    pub export: Handle<UiNode>,
+    pub restart: Handle<UiNode>,
+    pub menu: Handle<UiNode>,
+    text: Handle<UiNode>,
+}
+
+impl SummaryScreen {
+    //builds the hidden summary screen in the middle of the screen
+    pub fn build(ui: &mut UserInterface, screen: Handle<UiNode>) -> Self {
+        let text = TextBuilder::new(WidgetBuilder::new().with_margin(Thickness::uniform(8.0)))
+            .with_font_size(SUMMARY_FONT_SIZE.into())
+            .build(&mut ui.build_ctx());
+        let content = StackPanelBuilder::new(WidgetBuilder::new().with_child(text))
+            .with_orientation(Orientation::Vertical)
+            .build(&mut ui.build_ctx());
+        let buttons = hud::build_button_row(ui, content);
+        let export = hud::build_button(ui, "Export", buttons);
+        let restart = hud::build_button(ui, "Restart", buttons);
+        let menu = hud::build_button(ui, "Menu", buttons);
+
+        let panel = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Center)
+                .with_background(Brush::Solid(Color::from_rgba(0, 0, 0, 200)).into())
+                .with_child(content),
+        )
+        .build(&mut ui.build_ctx());
+        ui.link_nodes(panel, screen, false);
+
+        Self {
+            panel,
+            export,
+            restart,
+            menu,
+            text,
+        }
+    }
+
+    pub fn show(&self, ui: &UserInterface, summary: &RunSummary) {
+        ui.send_message(TextMessage::text(
+            self.text,
+            MessageDirection::ToWidget,
+            summary.to_string(),
+        ));
+        self.set_visible(ui, true);
+    }
+
+    pub fn set_visible(&self, ui: &UserInterface, visible: bool) {
+        ui.send_message(WidgetMessage::visibility(
+            self.panel,
+            MessageDirection::ToWidget,
+            visible,
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use fyrox::{
+    core::{color::Color, pool::Handle},
+    gui::{
+        border::BorderBuilder,
+        brush::Brush,
+        message::MessageDirection,
+        scroll_bar::{ScrollBarBuilder, ScrollBarMessage},
+        stack_panel::StackPanelBuilder,
+        widget::{WidgetBuilder, WidgetMessage},
+        HorizontalAlignment, Orientation, Thickness, UiNode, UserInterface, VerticalAlignment,
+    },
+};
+
+use crate::hud;
+
+const SLIDER_WIDTH: f32 = 600.0;
+const SLIDER_HEIGHT: f32 = 24.0;
+
+//the playback controls shown along the bottom of the screen in replay mode
+#[derive(Debug, Default)]
+pub struct Timeline {
+    pub panel: Handle<UiNode>,
+    pub slider: Handle<UiNode>,
+    pub play: Handle<UiNode>,
+    pub rewind: Handle<UiNode>,
+}
+
+impl Timeline {
+    //builds the hidden timeline at the bottom of the screen
+    pub fn build(ui: &mut UserInterface, screen: Handle<UiNode>) -> Self {
+        let slider = ScrollBarBuilder::new(
+            WidgetBuilder::new()
+                .with_width(SLIDER_WIDTH)
+                .with_height(SLIDER_HEIGHT)
+                .with_margin(Thickness::uniform(4.0)),
+        )
+        .with_orientation(Orientation::Horizontal)
+        .with_min(0.0)
+        .with_max(1.0)
+        .with_value(0.0)
+        .with_step(1.0)
+        .build(&mut ui.build_ctx());
+        let content = StackPanelBuilder::new(WidgetBuilder::new())
+            .with_orientation(Orientation::Horizontal)
+            .build(&mut ui.build_ctx());
+        let rewind = hud::build_button(ui, "Rewind", content);
+        let play = hud::build_button(ui, "Play", content);
+        ui.link_nodes(slider, content, false);
+
+        let panel = BorderBuilder::new(
+            WidgetBuilder::new()
+                .with_visibility(false)
+                .with_horizontal_alignment(HorizontalAlignment::Center)
+                .with_vertical_alignment(VerticalAlignment::Bottom)
+                .with_margin(Thickness::uniform(8.0))
+                .with_background(Brush::Solid(Color::from_rgba(0, 0, 0, 160)).into())
+                .with_child(content),
+        )
+        .build(&mut ui.build_ctx());
+        ui.link_nodes(panel, screen, false);
+
+        Self {
+            panel,
+            slider,
+            play,
+            rewind,
+        }
+    }
+
+    //shows the timeline covering the given ticks
+    pub fn show(&self, ui: &UserInterface, first_tick: u64, last_tick: u64) {
+        for message in [
+            ScrollBarMessage::min_value(self.slider, MessageDirection::ToWidget, first_tick as f32),
+            ScrollBarMessage::max_value(self.slider, MessageDirection::ToWidget, last_tick as f32),
+            ScrollBarMessage::value(self.slider, MessageDirection::ToWidget, first_tick as f32),
+            WidgetMessage::visibility(self.panel, MessageDirection::ToWidget, true),
+        ] {
+            ui.send_message(message);
+        }
+    }
+
+    pub fn set_tick(&self, ui: &UserInterface, tick: u64) {
+        ui.send_message(ScrollBarMessage::value(
+            self.slider,
+            MessageDirection::ToWidget,
+            tick as f32,
+        ));
+    }
+}
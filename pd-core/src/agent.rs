@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{genome::Genome, memory::Memory, strategy::StrategyId};
+
+pub type AgentId = u64;
+
+//a single bugster as seen by the simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Agent {
+    pub id: AgentId,
+    pub strategy: StrategyId,
+    pub healthpoints: i64,
+    pub memory: Memory,
+    //the agent this one split off from, founders have none
+    #[serde(default)]
+    pub parent: Option<AgentId>,
+    //how many splits separate the agent from its founder, founders are generation 0
+    #[serde(default)]
+    pub generation: u32,
+    //the tick the agent appeared
+    #[serde(default)]
+    pub born: u64,
+    //the agent's own lookup table when its strategy is a genome, it plays this instead of the
+    //strategy's
+    #[serde(default)]
+    pub genome: Option<Genome>,
+}
+
+impl Agent {
+    pub fn new(id: AgentId, strategy: StrategyId, healthpoints: i64) -> Self {
+        Self {
+            id,
+            strategy,
+            healthpoints,
+            memory: Memory::default(),
+            parent: None,
+            generation: 0,
+            born: 0,
+            genome: None,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.healthpoints > 0
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+
+use crate::{
+    agent::AgentId,
+    clock::{SimClock, TICK_DT},
+    encounter::Encounter,
+    public_goods::Interaction,
+    reproduction::Reproduction,
+    rng::SimRng,
+    simulation::Simulation,
+    strategy::StrategyId,
+};
+
+//the half size of the arena, agents spawn anywhere inside it
+pub const HALF_WIDTH: f32 = 8.0;
+pub const HALF_HEIGHT: f32 = 3.5;
+//the fastest an agent moves on its own
+pub const MAX_SPEED: f32 = 15.0;
+//how long an agent keeps its direction before picking a new one, in seconds
+pub const MIN_WAIT_TIME: f32 = 3.0;
+pub const MAX_WAIT_TIME: f32 = 5.0;
+//the side of an agent at BASE_HEALTH, it grows and shrinks with the square root of the
+//difference
+pub const BASE_SIZE: f32 = 0.5;
+pub const BASE_HEALTH: i64 = 10;
+const SCALE_FACTOR: f32 = 0.1;
+//how hard two agents that played are knocked apart
+pub const BOUNCE_FORCE: f32 = -6.0;
+//agents this close count as touching, the solid bodies never quite overlap
+const CONTACT_MARGIN: f32 = 0.01;
+
+//the side of an agent's square with the given health
+pub fn body_size(healthpoints: i64) -> f32 {
+    if healthpoints >= BASE_HEALTH {
+        SCALE_FACTOR * (healthpoints as f32 - BASE_HEALTH as f32).sqrt() + BASE_SIZE
+    } else {
+        -SCALE_FACTOR * (-healthpoints as f32 + BASE_HEALTH as f32).sqrt() + BASE_SIZE
+    }
+}
+
+//picks a new direction for an agent moving at up to `speed`, returning the impulse and how many
+//ticks to keep it for
+pub fn wander(speed: f32, rng: &mut SimRng) -> ([f32; 2], u64) {
+    let x_speed = rng.random_range(-speed..=speed);
+    let y_speed = rng.random_range(-1.0..=1.0) * (speed - x_speed.abs());
+    let wait = SimClock::ticks(rng.random_range(MIN_WAIT_TIME..=MAX_WAIT_TIME));
+    ([x_speed, y_speed], wait)
+}
+
+//where an offspring appears, just clear of its parent in a random direction and inside the
+//arena
+pub fn offspring_position(
+    parent: [f32; 2],
+    parent_healthpoints: i64,
+    healthpoints: i64,
+    rng: &mut SimRng,
+) -> [f32; 2] {
+    let angle = rng.random_range(0.0..std::f32::consts::TAU);
+    let distance =
+        (body_size(parent_healthpoints) + body_size(healthpoints)) / 2.0 + CONTACT_MARGIN;
+    [
+        (parent[0] + angle.cos() * distance).clamp(-HALF_WIDTH, HALF_WIDTH),
+        (parent[1] + angle.sin() * distance).clamp(-HALF_HEIGHT, HALF_HEIGHT),
+    ]
+}
+
+//one agent's body in the arena
+#[derive(Debug, Clone, PartialEq)]
+struct Body {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    next_move_tick: u64,
+}
+
+//a stand in for the physics scene when there is no window, every agent is a square that
+//wanders the same way and plays whoever it touches, but movement is plain kinematics so runs
+//are close to the game rather than identical to it
+#[derive(Debug, Clone, Default)]
+pub struct Arena {
+    bodies: BTreeMap<AgentId, Body>,
+}
+
+impl Arena {
+    //adds an agent at a random spot, drawing the spot before the agent is created just like
+    //the game does
+    pub fn spawn(
+        &mut self,
+        simulation: &mut Simulation,
+        strategy: StrategyId,
+        healthpoints: i64,
+    ) -> AgentId {
+        let x = simulation.rng.random_range(-HALF_WIDTH..=HALF_WIDTH);
+        let y = simulation.rng.random_range(-HALF_HEIGHT..=HALF_HEIGHT);
+        let agent = simulation.spawn(strategy, healthpoints);
+        self.bodies.insert(
+            agent,
+            Body {
+                position: [x, y],
+                velocity: [0.0, 0.0],
+                next_move_tick: 0,
+            },
+        );
+        agent
+    }
+
+    //splits every agent over the reproduction threshold, the offspring is placed next to its
+    //parent and starts still
+    pub fn reproduce(&mut self, simulation: &mut Simulation, reproduction: &Reproduction) {
+        for parent in simulation.due_to_split(reproduction) {
+            let (Some(position), Some(birth)) = (
+                self.bodies.get(&parent).map(|body| body.position),
+                simulation.split(parent, reproduction),
+            ) else {
+                continue;
+            };
+            let parent_healthpoints = simulation
+                .population
+                .get(parent)
+                .map_or(BASE_HEALTH, |a| a.healthpoints);
+            let position = offspring_position(
+                position,
+                parent_healthpoints,
+                birth.healthpoints,
+                &mut simulation.rng,
+            );
+            let agent = simulation.birth(birth);
+            self.bodies.insert(
+                agent,
+                Body {
+                    position,
+                    velocity: [0.0, 0.0],
+                    next_move_tick: 0,
+                },
+            );
+        }
+    }
+
+    //where every agent is, sorted by agent
+    pub fn positions(&self) -> Vec<(AgentId, [f32; 2])> {
+        self.bodies
+            .iter()
+            .map(|(agent, body)| (*agent, body.position))
+            .collect()
+    }
+
+    //moves every agent one tick, then plays and knocks apart every pair that touches,
+    //returning the encounters played, in the public goods mode touching agents are only
+    //pushed apart
+    pub fn tick(
+        &mut self,
+        simulation: &mut Simulation,
+        interaction: &Interaction,
+    ) -> Vec<Encounter> {
+        let tick = simulation.clock.tick();
+        for body in self.bodies.values_mut() {
+            if tick >= body.next_move_tick {
+                let (impulse, wait) = wander(MAX_SPEED, &mut simulation.rng);
+                body.velocity[0] += impulse[0];
+                body.velocity[1] += impulse[1];
+                body.next_move_tick = tick + wait;
+            }
+            body.move_by(TICK_DT);
+        }
+
+        let encounters = if interaction.is_pairwise() {
+            let touching = self.touching(simulation);
+            simulation.resolve(&touching)
+        } else {
+            Vec::new()
+        };
+        for encounter in &encounters {
+            let (a, b) = (encounter.a.agent, encounter.b.agent);
+            let (Some(from_a), Some(from_b)) = (self.bodies.get(&a), self.bodies.get(&b)) else {
+                continue;
+            };
+            let direction = [
+                from_b.position[0] - from_a.position[0],
+                from_b.position[1] - from_a.position[1],
+            ];
+            for (agent, sign) in [(a, 1.0), (b, -1.0)] {
+                if let Some(body) = self.bodies.get_mut(&agent) {
+                    body.velocity[0] += sign * direction[0] * BOUNCE_FORCE;
+                    body.velocity[1] += sign * direction[1] * BOUNCE_FORCE;
+                }
+            }
+        }
+        self.bodies
+            .retain(|agent, _| simulation.population.get(*agent).is_some());
+        self.separate(simulation);
+        encounters
+    }
+
+    //half the side of an agent's square
+    fn half_extent(simulation: &Simulation, agent: AgentId) -> f32 {
+        simulation
+            .population
+            .get(agent)
+            .map_or(BASE_SIZE, |a| body_size(a.healthpoints))
+            / 2.0
+    }
+
+    //every pair of agents whose squares touch, in agent order
+    fn touching(&self, simulation: &Simulation) -> Vec<(AgentId, AgentId)> {
+        let bodies: Vec<_> = self
+            .bodies
+            .iter()
+            .map(|(agent, body)| (*agent, body.position, Self::half_extent(simulation, *agent)))
+            .collect();
+        let mut touching = Vec::new();
+        for (i, (a, position_a, extent_a)) in bodies.iter().enumerate() {
+            for (b, position_b, extent_b) in &bodies[i + 1..] {
+                let reach = extent_a + extent_b + CONTACT_MARGIN;
+                if (position_a[0] - position_b[0]).abs() <= reach
+                    && (position_a[1] - position_b[1]).abs() <= reach
+                {
+                    touching.push((*a, *b));
+                }
+            }
+        }
+        touching
+    }
+
+    //pushes overlapping agents out of eachother along the axis they overlap least on, the way
+    //solid bodies would
+    fn separate(&mut self, simulation: &Simulation) {
+        let agents: Vec<_> = self.bodies.keys().copied().collect();
+        for (i, a) in agents.iter().enumerate() {
+            for b in &agents[i + 1..] {
+                let reach = Self::half_extent(simulation, *a) + Self::half_extent(simulation, *b);
+                let (Some(position_a), Some(position_b)) = (
+                    self.bodies.get(a).map(|body| body.position),
+                    self.bodies.get(b).map(|body| body.position),
+                ) else {
+                    continue;
+                };
+                let delta = [position_b[0] - position_a[0], position_b[1] - position_a[1]];
+                let overlap = [reach - delta[0].abs(), reach - delta[1].abs()];
+                if overlap[0] <= 0.0 || overlap[1] <= 0.0 {
+                    continue;
+                }
+                let axis = usize::from(overlap[1] < overlap[0]);
+                let push = overlap[axis] / 2.0 * if delta[axis] < 0.0 { -1.0 } else { 1.0 };
+                for (agent, sign) in [(a, -1.0), (b, 1.0)] {
+                    if let Some(body) = self.bodies.get_mut(agent) {
+                        body.position[axis] += sign * push;
+                        body.keep_inside();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Body {
+    //moves along the velocity, capped at MAX_SPEED, bouncing off the walls
+    fn move_by(&mut self, dt: f32) {
+        let speed = self.velocity[0].hypot(self.velocity[1]);
+        if speed > MAX_SPEED {
+            self.velocity = self.velocity.map(|v| v * MAX_SPEED / speed);
+        }
+        self.position[0] += self.velocity[0] * dt;
+        self.position[1] += self.velocity[1] * dt;
+        for (axis, limit) in [(0, HALF_WIDTH), (1, HALF_HEIGHT)] {
+            if self.position[axis].abs() > limit {
+                self.velocity[axis] = -self.velocity[axis];
+            }
+        }
+        self.keep_inside();
+    }
+
+    fn keep_inside(&mut self) {
+        self.position[0] = self.position[0].clamp(-HALF_WIDTH, HALF_WIDTH);
+        self.position[1] = self.position[1].clamp(-HALF_HEIGHT, HALF_HEIGHT);
+    }
+}
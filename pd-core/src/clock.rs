@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+//how many simulation ticks run per second of simulated time
+pub const TICK_RATE: u32 = 60;
+pub const TICK_DT: f32 = 1.0 / TICK_RATE as f32;
+//the most frame time a single frame may add, so a long stall doesn't queue up a burst of ticks
+const MAX_FRAME_TIME: f32 = 0.25;
+//the speed multipliers the clock can run at, slowest first
+pub const SPEEDS: [f32; 7] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0, 16.0];
+const NORMAL_SPEED: usize = 2;
+
+//a fixed timestep clock, frame time is gathered up and spent in whole ticks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimClock {
+    tick: u64,
+    accumulator: f32,
+    //index into SPEEDS
+    speed: usize,
+    paused: bool,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self {
+            tick: 0,
+            accumulator: 0.0,
+            speed: NORMAL_SPEED,
+            paused: false,
+        }
+    }
+}
+
+impl SimClock {
+    //converts a duration in seconds to a whole number of ticks
+    pub fn ticks(seconds: f32) -> u64 {
+        (seconds * TICK_RATE as f32).round() as u64
+    }
+
+    //the number of ticks processed so far
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    //adds the time that passed since the last frame, scaled by the speed so a faster clock
+    //runs more ticks per frame rather than longer ones, nothing is added while paused
+    pub fn accumulate(&mut self, dt: f32) {
+        if self.paused {
+            return;
+        }
+        self.accumulator += dt.min(MAX_FRAME_TIME) * self.speed();
+    }
+
+    //consumes one tick worth of time if there is enough, call until it returns false
+    pub fn next_tick(&mut self) -> bool {
+        if self.accumulator < TICK_DT {
+            return false;
+        }
+        self.accumulator -= TICK_DT;
+        self.tick += 1;
+        true
+    }
+
+    //moves straight on to the next tick, for runs that don't follow the frame time
+    pub fn advance(&mut self) {
+        self.tick += 1;
+    }
+
+    //queues exactly one tick, used to step through a paused run
+    pub fn step(&mut self) {
+        self.accumulator = self.accumulator.max(0.0) + TICK_DT;
+    }
+
+    //how far the leftover time is into the next tick, for interpolating between ticks
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / TICK_DT).clamp(0.0, 1.0)
+    }
+
+    pub fn speed(&self) -> f32 {
+        SPEEDS[self.speed]
+    }
+
+    //moves one speed step up, or down when `faster` is false, stopping at either end
+    pub fn change_speed(&mut self, faster: bool) {
+        self.speed = if faster {
+            (self.speed + 1).min(SPEEDS.len() - 1)
+        } else {
+            self.speed.saturating_sub(1)
+        };
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //runs every tick one frame of `dt` seconds makes due, returning how many there were
+    fn frame(clock: &mut SimClock, dt: f32) -> u64 {
+        clock.accumulate(dt);
+        let mut ticks = 0;
+        while clock.next_tick() {
+            ticks += 1;
+        }
+        ticks
+    }
+
+    #[test]
+    fn frame_time_is_spent_in_whole_ticks() {
+        let mut clock = SimClock::default();
+        assert_eq!(frame(&mut clock, 1.0 / 30.0), 2);
+        assert_eq!(clock.tick(), 2);
+        assert_eq!(clock.alpha(), 0.0);
+        //the leftover half tick waits for the next frame
+        assert_eq!(frame(&mut clock, 1.5 / 60.0), 1);
+        assert!((clock.alpha() - 0.5).abs() < 1e-3, "{}", clock.alpha());
+        assert_eq!(frame(&mut clock, 0.75 / 60.0), 1);
+        assert!((clock.alpha() - 0.25).abs() < 1e-3, "{}", clock.alpha());
+        assert_eq!(clock.tick(), 4);
+    }
+
+    #[test]
+    fn long_frames_are_clamped() {
+        let mut clock = SimClock::default();
+        assert_eq!(frame(&mut clock, 10.0), SimClock::ticks(MAX_FRAME_TIME));
+        assert_eq!(SimClock::ticks(MAX_FRAME_TIME), 15);
+    }
+
+    #[test]
+    fn a_paused_clock_only_steps() {
+        let mut clock = SimClock::default();
+        clock.set_paused(true);
+        assert_eq!(frame(&mut clock, 1.0 / 30.0), 0);
+        clock.step();
+        assert_eq!(frame(&mut clock, 1.0 / 30.0), 1);
+        assert_eq!(clock.tick(), 1);
+        clock.advance();
+        assert_eq!(clock.tick(), 2);
+    }
+
+    #[test]
+    fn speed_steps_stop_at_either_end() {
+        let mut clock = SimClock::default();
+        assert_eq!(clock.speed(), 1.0);
+        let mut seen = vec![clock.speed()];
+        for _ in 0..SPEEDS.len() {
+            clock.change_speed(true);
+            seen.push(clock.speed());
+        }
+        assert_eq!(seen, [1.0, 2.0, 4.0, 8.0, 16.0, 16.0, 16.0, 16.0]);
+        for _ in 0..SPEEDS.len() {
+            clock.change_speed(false);
+        }
+        assert_eq!(clock.speed(), 0.25);
+        clock.change_speed(true);
+        assert_eq!(clock.speed(), 0.5);
+    }
+
+    #[test]
+    fn ticks_per_frame_scale_with_speed() {
+        let mut clock = SimClock::default();
+        for _ in 0..NORMAL_SPEED {
+            clock.change_speed(false);
+        }
+        for speed in SPEEDS {
+            assert_eq!(clock.speed(), speed);
+            let ticks: u64 = (0..60).map(|_| frame(&mut clock, 1.0 / 60.0)).sum();
+            assert_eq!(ticks, (60.0 * speed) as u64, "{speed}x");
+            clock.change_speed(true);
+        }
+        //the frame time is clamped before the speed is applied
+        assert_eq!(
+            frame(&mut clock, 10.0),
+            16 * SimClock::ticks(MAX_FRAME_TIME)
+        );
+    }
+
+    #[test]
+    fn seconds_convert_to_ticks() {
+        assert_eq!(SimClock::ticks(2.0), 2 * TICK_RATE as u64);
+        assert_eq!(SimClock::ticks(0.5), 30);
+        assert_eq!(SimClock::ticks(0.0), 0);
+    }
+}
@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::AgentId, strategy::Move, strategy::StrategyId};
+
+//ticks before the same pair can play each other again
+pub const ENCOUNTER_COOLDOWN: u64 = 30;
+
+//one agent's part in an encounter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Side {
+    pub agent: AgentId,
+    pub strategy: StrategyId,
+    pub played: Move,
+    //the health actually gained or lost, accounting for health dropping to 0
+    pub payoff: i64,
+    pub healthpoints: i64,
+    pub died: bool,
+}
+
+//a single symmetric game between two agents, both payoffs are applied together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Encounter {
+    pub tick: u64,
+    pub a: Side,
+    pub b: Side,
+}
+
+impl Encounter {
+    pub fn sides(&self) -> [&Side; 2] {
+        [&self.a, &self.b]
+    }
+}
+
+//remembers when each pair last played so overlapping agents aren't scored every tick
+//saved as a list since json maps can't have pairs as keys
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(
+    from = "Vec<(AgentId, AgentId, u64)>",
+    into = "Vec<(AgentId, AgentId, u64)>"
+)]
+pub struct Cooldowns {
+    last_played: HashMap<(AgentId, AgentId), u64>,
+}
+
+impl From<Vec<(AgentId, AgentId, u64)>> for Cooldowns {
+    fn from(pairs: Vec<(AgentId, AgentId, u64)>) -> Self {
+        Self {
+            last_played: pairs
+                .into_iter()
+                .map(|(a, b, tick)| ((a, b), tick))
+                .collect(),
+        }
+    }
+}
+
+impl From<Cooldowns> for Vec<(AgentId, AgentId, u64)> {
+    fn from(cooldowns: Cooldowns) -> Self {
+        let mut pairs: Vec<_> = cooldowns
+            .last_played
+            .into_iter()
+            .map(|((a, b), tick)| (a, b, tick))
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+impl Cooldowns {
+    //the same key for a pair regardless of order
+    pub fn key(a: AgentId, b: AgentId) -> (AgentId, AgentId) {
+        (a.min(b), a.max(b))
+    }
+
+    pub fn ready(&self, a: AgentId, b: AgentId, tick: u64) -> bool {
+        self.last_played
+            .get(&Self::key(a, b))
+            .is_none_or(|last| tick >= last + ENCOUNTER_COOLDOWN)
+    }
+
+    pub fn start(&mut self, a: AgentId, b: AgentId, tick: u64) {
+        self.last_played.insert(Self::key(a, b), tick);
+    }
+
+    //drops cooldowns that have already run out
+    pub fn prune(&mut self, tick: u64) {
+        self.last_played
+            .retain(|_, last| tick < *last + ENCOUNTER_COOLDOWN);
+    }
+}
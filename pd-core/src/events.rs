@@ -0,0 +1,262 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    agent::{Agent, AgentId},
+    encounter::{Encounter, Side},
+    payoff::PayoffMatrix,
+    public_goods::Gathering,
+    strategy::{Move, StrategyRegistry},
+};
+
+//one agent's part in an encounter event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SideEvent {
+    pub agent: AgentId,
+    pub strategy: String,
+    #[serde(rename = "move")]
+    pub played: Move,
+    pub payoff: i64,
+    pub healthpoints: i64,
+    pub died: bool,
+}
+
+impl SideEvent {
+    fn of(side: &Side, strategies: &StrategyRegistry) -> Self {
+        SideEvent {
+            agent: side.agent,
+            strategy: strategies
+                .get(side.strategy)
+                .map_or_else(|| side.strategy.to_string(), |s| s.name().to_owned()),
+            played: side.played,
+            payoff: side.payoff,
+            healthpoints: side.healthpoints,
+            died: side.died,
+        }
+    }
+}
+
+//everything that happens in a run that analysis may care about
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    //written once at the start of every log so it can be tied back to its run
+    Run {
+        seed: u64,
+        payoffs: PayoffMatrix,
+        strategies: Vec<String>,
+    },
+    Encounter {
+        tick: u64,
+        a: SideEvent,
+        b: SideEvent,
+    },
+    //a public goods game in a zone, a member's move is whether it contributed
+    Gathering {
+        tick: u64,
+        zone: usize,
+        pot: i64,
+        members: Vec<SideEvent>,
+    },
+    //an agent split in two, the health is what each has afterwards
+    Birth {
+        tick: u64,
+        agent: AgentId,
+        parent: AgentId,
+        strategy: String,
+        healthpoints: i64,
+        parent_healthpoints: i64,
+        generation: u32,
+        //the offspring follows another strategy than its parent
+        mutated: bool,
+    },
+}
+
+impl Event {
+    pub fn run(seed: u64, payoffs: &PayoffMatrix, strategies: &StrategyRegistry) -> Self {
+        Event::Run {
+            seed,
+            payoffs: *payoffs,
+            strategies: strategies
+                .iter()
+                .map(|(_, s)| s.name().to_owned())
+                .collect(),
+        }
+    }
+
+    pub fn encounter(encounter: &Encounter, strategies: &StrategyRegistry) -> Self {
+        Event::Encounter {
+            tick: encounter.tick,
+            a: SideEvent::of(&encounter.a, strategies),
+            b: SideEvent::of(&encounter.b, strategies),
+        }
+    }
+
+    pub fn gathering(gathering: &Gathering, strategies: &StrategyRegistry) -> Self {
+        Event::Gathering {
+            tick: gathering.tick,
+            zone: gathering.zone,
+            pot: gathering.pot,
+            members: gathering
+                .members
+                .iter()
+                .map(|side| SideEvent::of(side, strategies))
+                .collect(),
+        }
+    }
+
+    pub fn birth(tick: u64, agent: &Agent, parent: &Agent, strategies: &StrategyRegistry) -> Self {
+        Event::Birth {
+            tick,
+            agent: agent.id,
+            parent: parent.id,
+            strategy: strategies
+                .get(agent.strategy)
+                .map_or_else(|| agent.strategy.to_string(), |s| s.name().to_owned()),
+            healthpoints: agent.healthpoints,
+            parent_healthpoints: parent.healthpoints,
+            generation: agent.generation,
+            mutated: agent.strategy != parent.strategy,
+        }
+    }
+}
+
+//collects the events of a run in memory and optionally streams them to a json lines file
+#[derive(Debug, Default)]
+pub struct EventBus {
+    events: Vec<Event>,
+    sink: Option<BufWriter<File>>,
+    //the error that stopped the stream, kept until someone reports it
+    sink_error: Option<io::Error>,
+}
+
+impl EventBus {
+    //a bus that already holds the events of a restored run
+    pub fn from_events(events: Vec<Event>) -> Self {
+        Self {
+            events,
+            ..Self::default()
+        }
+    }
+
+    //starts writing every event, including the ones already emitted, to a .jsonl file
+    pub fn stream_to(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut sink = BufWriter::new(File::create(path)?);
+        for event in &self.events {
+            write_line(&mut sink, event)?;
+        }
+        self.sink = Some(sink);
+        Ok(())
+    }
+
+    //a file that fails to write stops the stream but never the run, see `take_error`
+    pub fn emit(&mut self, event: Event) {
+        if let Some(sink) = &mut self.sink {
+            if let Err(e) = write_line(sink, &event) {
+                self.sink = None;
+                self.sink_error = Some(e);
+            }
+        }
+        self.events.push(event);
+    }
+
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.sink_error.take()
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.sink {
+            Some(sink) => sink.flush(),
+            None => Ok(()),
+        }
+    }
+
+    //writes every event so far to a .jsonl file in one go
+    pub fn export(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        for event in &self.events {
+            write_line(&mut file, event)?;
+        }
+        file.flush()
+    }
+}
+
+fn write_line(writer: &mut impl Write, event: &Event) -> io::Result<()> {
+    serde_json::to_writer(&mut *writer, event)?;
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::simulation::Simulation;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("pd_core_{name}_{}.jsonl", std::process::id()))
+    }
+
+    //the parsed lines of a json lines file, which is removed
+    fn read_lines(path: &Path) -> Vec<serde_json::Value> {
+        let text = fs::read_to_string(path).unwrap();
+        fs::remove_file(path).unwrap();
+        text.lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    //a run event and one Coop against Greed encounter
+    fn simulation() -> Simulation {
+        let mut simulation = Simulation::new(7, PayoffMatrix::default(), Default::default());
+        let coop = simulation.spawn(0, 10);
+        let greed = simulation.spawn(1, 10);
+        simulation.clock.advance();
+        simulation.resolve(&[(coop, greed)]);
+        simulation
+    }
+
+    #[test]
+    fn exports_one_event_per_line() {
+        let simulation = simulation();
+        let path = temp_path("export");
+        simulation.events.export(&path).unwrap();
+        let lines = read_lines(&path);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "run");
+        assert_eq!(lines[0]["seed"], 7);
+        assert_eq!(lines[1]["type"], "encounter");
+        assert_eq!(lines[1]["tick"], 1);
+        assert_eq!(lines[1]["a"]["strategy"], "Coop");
+        assert_eq!(lines[1]["a"]["move"], "cooperate");
+        assert_eq!(lines[1]["b"]["move"], "defect");
+        assert_eq!(lines[1]["b"]["payoff"], 3);
+    }
+
+    #[test]
+    fn streams_earlier_and_later_events() {
+        let mut simulation = simulation();
+        let path = temp_path("stream");
+        simulation.events.stream_to(&path).unwrap();
+        let (coop, greed) = (0, 1);
+        for _ in 0..crate::encounter::ENCOUNTER_COOLDOWN {
+            simulation.clock.advance();
+        }
+        simulation.resolve(&[(coop, greed)]);
+        simulation.events.flush().unwrap();
+        assert!(simulation.events.take_error().is_none());
+        let lines = read_lines(&path);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines.len(), simulation.events.events().len());
+        assert_eq!(lines[2]["tick"], 1 + crate::encounter::ENCOUNTER_COOLDOWN);
+    }
+}
@@ -0,0 +1,425 @@
+use std::{
+    fmt, fs,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    genome::Genome,
+    payoff::{GameType, PayoffMatrix},
+    rng::{self, SimRng},
+    scenario::{parse, ScenarioError},
+    strategies::Evolved,
+    strategy::{Strategy, StrategyId, StrategyRegistry},
+    tournament::{Tally, Tournament},
+};
+
+//colours handed to saved genomes in turn
+const PALETTE: [[u8; 3]; 5] = [
+    [230, 150, 30],
+    [30, 190, 190],
+    [190, 60, 190],
+    [140, 200, 40],
+    [220, 90, 90],
+];
+
+//the settings of an offline genetic algorithm, read from a ron file, every generation plays
+//round robin matches under the tournament rules and breeds the next from the fittest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Evolution {
+    //how many past rounds the genomes look at, 1 or 2
+    pub memory: usize,
+    //genomes per generation
+    pub population: usize,
+    pub generations: u32,
+    //the most rounds a match lasts
+    pub rounds: u32,
+    //the chance another round follows each round, left out every match lasts `rounds`
+    pub continuation: Option<f64>,
+    //the chance a move comes out the opposite of what the genome picked
+    pub noise: f64,
+    //the chance a genome remembers the opposite of what its opponent played
+    pub perception: f64,
+    //the game the payoffs have to be, a prisoner's dilemma unless set
+    pub game: GameType,
+    //left out, the game's built in matrix is used
+    pub payoffs: Option<PayoffMatrix>,
+    //named strategies every genome also plays each generation, left empty genomes only play
+    //eachother
+    pub opponents: Vec<String>,
+    //the chance a child is a cross of two parents rather than a copy of one
+    pub crossover: f64,
+    //the chance each gene of a child flips
+    pub mutation: f64,
+    //the fittest genomes carried over unchanged
+    pub elite: usize,
+    //how many genomes are drawn to pick each parent, the fittest of them wins
+    pub selection: usize,
+    //how many of the final genomes are saved as strategies
+    pub keep: usize,
+    pub seed: Option<u64>,
+}
+
+//how one generation did
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GenerationStats {
+    pub generation: u32,
+    pub best: Genome,
+    //mean payoff per round
+    pub best_fitness: f64,
+    pub mean_fitness: f64,
+    pub cooperation_rate: f64,
+    //the mean share of genes the genomes have in common with tit for tat
+    pub tft_agreement: f64,
+}
+
+//the outcome of an evolution run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvolutionResult {
+    pub seed: u64,
+    pub generations: Vec<GenerationStats>,
+    //the last generation and its fitness, fittest first
+    pub population: Vec<(Genome, f64)>,
+}
+
+impl Default for Evolution {
+    fn default() -> Self {
+        Self {
+            memory: 1,
+            population: 40,
+            generations: 100,
+            rounds: 150,
+            continuation: None,
+            noise: 0.0,
+            perception: 0.0,
+            game: GameType::default(),
+            payoffs: None,
+            opponents: Vec::new(),
+            crossover: 0.7,
+            mutation: 0.02,
+            elite: 2,
+            selection: 3,
+            keep: 3,
+            seed: None,
+        }
+    }
+}
+
+impl Evolution {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let text = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        Self::from_ron(&text)
+    }
+
+    //parses an evolution run and checks its payoffs are a valid game of its type and its memory exists
+    pub fn from_ron(text: &str) -> Result<Self, ScenarioError> {
+        let evolution: Evolution = parse(text)?;
+        evolution
+            .payoffs()
+            .validate_as(evolution.game)
+            .map_err(ScenarioError::Payoffs)?;
+        Genome::tit_for_tat(evolution.memory).map_err(ScenarioError::Genome)?;
+        Ok(evolution)
+    }
+
+    //the payoffs every match is played with
+    pub fn payoffs(&self) -> PayoffMatrix {
+        self.game.or_preset(self.payoffs)
+    }
+
+    //the named opponents, in registry order
+    pub fn opponents(&self, registry: &StrategyRegistry) -> Result<Vec<StrategyId>, ScenarioError> {
+        let mut opponents = self
+            .opponents
+            .iter()
+            .map(|name| {
+                registry
+                    .find(name)
+                    .ok_or_else(|| ScenarioError::UnknownStrategy(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        opponents.sort_unstable();
+        opponents.dedup();
+        Ok(opponents)
+    }
+
+    //evolves random genomes for the configured generations, `progress` is told about every
+    //generation as it finishes
+    pub fn run(
+        &self,
+        seed: u64,
+        registry: &StrategyRegistry,
+        mut progress: impl FnMut(&GenerationStats),
+    ) -> Result<EvolutionResult, ScenarioError> {
+        let opponents: Vec<_> = self
+            .opponents(registry)?
+            .into_iter()
+            .filter_map(|id| registry.get(id))
+            .collect();
+        let tit_for_tat = Genome::tit_for_tat(self.memory).map_err(ScenarioError::Genome)?;
+        let mut rng = rng::seeded(seed);
+        let mut genomes = (0..self.population.max(2))
+            .map(|_| Genome::random(self.memory, &mut rng))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ScenarioError::Genome)?;
+
+        let mut generations = Vec::new();
+        let mut ranked = Vec::new();
+        for generation in 0..self.generations.max(1) {
+            let (fitness, cooperation_rate) = self.evaluate(&genomes, &opponents, &mut rng);
+            ranked = genomes.iter().copied().zip(fitness).collect();
+            ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+            let n = ranked.len() as f64;
+            let stats = GenerationStats {
+                generation,
+                best: ranked[0].0,
+                best_fitness: ranked[0].1,
+                mean_fitness: ranked.iter().map(|(_, f)| f).sum::<f64>() / n,
+                cooperation_rate,
+                tft_agreement: ranked
+                    .iter()
+                    .map(|(g, _)| g.agreement(&tit_for_tat))
+                    .sum::<f64>()
+                    / n,
+            };
+            progress(&stats);
+            generations.push(stats);
+            if generation + 1 < self.generations {
+                genomes = self.breed(&ranked, &mut rng);
+            }
+        }
+
+        Ok(EvolutionResult {
+            seed,
+            generations,
+            population: ranked,
+        })
+    }
+
+    //the rules every match is played by
+    fn rules(&self) -> Tournament {
+        Tournament {
+            rounds: self.rounds,
+            continuation: self.continuation,
+            noise: self.noise,
+            perception: self.perception,
+            repetitions: 1,
+            game: self.game,
+            payoffs: self.payoffs,
+            strategies: Vec::new(),
+            seed: None,
+        }
+    }
+
+    //plays every genome against every other genome and every opponent, returning the mean
+    //payoff per round of each genome and how often the genomes cooperated overall
+    fn evaluate(
+        &self,
+        genomes: &[Genome],
+        opponents: &[&dyn Strategy],
+        rng: &mut SimRng,
+    ) -> (Vec<f64>, f64) {
+        let rules = self.rules();
+        let players: Vec<_> = genomes
+            .iter()
+            .map(|genome| Evolved {
+                name: String::new(),
+                genome: *genome,
+                color: [0; 3],
+            })
+            .collect();
+        let mut tallies = vec![Tally::default(); players.len()];
+        let mut add = |i: usize, tally: Tally| {
+            tallies[i].payoff += tally.payoff;
+            tallies[i].rounds += tally.rounds;
+            tallies[i].cooperations += tally.cooperations;
+        };
+        for i in 0..players.len() {
+            for j in i + 1..players.len() {
+                let [a, b] = rules.play_match(&players[i], &players[j], rng);
+                add(i, a);
+                add(j, b);
+            }
+            for opponent in opponents {
+                let [a, _] = rules.play_match(&players[i], *opponent, rng);
+                add(i, a);
+            }
+        }
+
+        let fitness = tallies
+            .iter()
+            .map(|t| t.payoff as f64 / t.rounds.max(1) as f64)
+            .collect();
+        let (cooperations, rounds) = tallies
+            .iter()
+            .fold((0, 0), |(c, r), t| (c + t.cooperations, r + t.rounds));
+        (fitness, cooperations as f64 / rounds.max(1) as f64)
+    }
+
+    //the next generation, the elite as they are and the rest bred from parents picked by
+    //tournament selection
+    fn breed(&self, ranked: &[(Genome, f64)], rng: &mut SimRng) -> Vec<Genome> {
+        let size = ranked.len();
+        let pick = |rng: &mut SimRng| {
+            //ranked is fittest first, so the lowest index drawn is the fittest
+            let best = (0..self.selection.max(1))
+                .map(|_| rng.random_range(0..size))
+                .min()
+                .unwrap_or(0);
+            ranked[best].0
+        };
+        let mut next: Vec<_> = ranked
+            .iter()
+            .take(self.elite.min(size))
+            .map(|(genome, _)| *genome)
+            .collect();
+        while next.len() < size {
+            let first = pick(rng);
+            let child = if self.crossover > 0.0 && rng.random_bool(self.crossover.min(1.0)) {
+                let second = pick(rng);
+                first.crossover(&second, rng)
+            } else {
+                first
+            };
+            next.push(child.mutate(self.mutation, rng));
+        }
+        next
+    }
+}
+
+impl EvolutionResult {
+    //writes one row per generation as csv
+    pub fn write_csv(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "seed,generation,best_fitness,mean_fitness,cooperation_rate,tft_agreement,best_genome"
+        )?;
+        for g in &self.generations {
+            writeln!(
+                writer,
+                "{},{},{:.4},{:.4},{:.3},{:.3},{}",
+                self.seed,
+                g.generation,
+                g.best_fitness,
+                g.mean_fitness,
+                g.cooperation_rate,
+                g.tft_agreement,
+                g.best
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn export_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut file)?;
+        file.flush()
+    }
+
+    //the fittest distinct genomes of the last generation as strategies named `<prefix>-1`,
+    //`<prefix>-2`, ...
+    pub fn strategies(&self, keep: usize, prefix: &str) -> Vec<Evolved> {
+        let mut kept: Vec<Genome> = Vec::new();
+        for (genome, _) in &self.population {
+            if kept.len() == keep {
+                break;
+            }
+            if !kept.contains(genome) {
+                kept.push(*genome);
+            }
+        }
+        kept.into_iter()
+            .enumerate()
+            .map(|(i, genome)| Evolved {
+                name: format!("{prefix}-{}", i + 1),
+                genome,
+                color: PALETTE[i % PALETTE.len()],
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for EvolutionResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>10}  {:>8}  {:>8}  {:>6}  {:>6}  Best genome",
+            "Generation", "Best", "Mean", "Coop", "TFT"
+        )?;
+        let last = self.generations.len().saturating_sub(1);
+        //every tenth generation and the last one
+        for g in self
+            .generations
+            .iter()
+            .filter(|g| g.generation.is_multiple_of(10) || g.generation as usize == last)
+        {
+            writeln!(
+                f,
+                "{:>10}  {:>8.3}  {:>8.3}  {:>5.1}%  {:>5.1}%  {}",
+                g.generation,
+                g.best_fitness,
+                g.mean_fitness,
+                g.cooperation_rate * 100.0,
+                g.tft_agreement * 100.0,
+                g.best
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "Seed: {}", self.seed)?;
+        if let Some((genome, fitness)) = self.population.first() {
+            writeln!(f, "Fittest genome: {genome} ({fitness:.3} per round)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small() -> Evolution {
+        Evolution {
+            population: 8,
+            generations: 4,
+            rounds: 20,
+            noise: 0.05,
+            opponents: vec!["TFT".to_owned(), "Greed".to_owned()],
+            ..Evolution::default()
+        }
+    }
+
+    #[test]
+    fn a_seed_always_evolves_the_same_genomes() {
+        let registry = StrategyRegistry::default();
+        let mut told = Vec::new();
+        let first = small()
+            .run(11, &registry, |stats| told.push(stats.generation))
+            .unwrap();
+        let second = small().run(11, &registry, |_| {}).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(told, [0, 1, 2, 3]);
+        assert_eq!(first.generations.len(), 4);
+        assert_eq!(first.population.len(), 8);
+        //fittest first
+        assert!(first.population.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert_ne!(small().run(12, &registry, |_| {}).unwrap(), first);
+    }
+
+    #[test]
+    fn unknown_opponents_are_refused() {
+        let evolution = Evolution {
+            opponents: vec!["Nobody".to_owned()],
+            ..small()
+        };
+        assert!(matches!(
+            evolution.run(1, &StrategyRegistry::default(), |_| {}),
+            Err(ScenarioError::UnknownStrategy(name)) if name == "Nobody"
+        ));
+    }
+}
@@ -0,0 +1,298 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{memory::History, strategy::Move};
+
+//the longest memory a genome can have, memory-two already takes 18 genes
+pub const MAX_MEMORY: usize = 2;
+
+//a strategy written out as a lookup table, the move to play after every combination of the
+//last `memory` rounds plus an opening move for each of the first `memory` rounds, written as a
+//string of C and D with the openings first, memory-one tit for tat is "CCDCD"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Genome {
+    memory: u8,
+    //bit i set means gene i is Defect
+    genes: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenomeError {
+    //only memory-one and memory-two genomes exist
+    Memory(usize),
+    //the string is not the length of any genome
+    Length(usize),
+    //a gene that isn't C or D
+    Gene(char),
+}
+
+impl Genome {
+    //how many genes a genome with the given memory has
+    pub fn length(memory: usize) -> usize {
+        memory + 4usize.pow(memory as u32)
+    }
+
+    pub fn new(memory: usize, genes: &[Move]) -> Result<Self, GenomeError> {
+        if !(1..=MAX_MEMORY).contains(&memory) {
+            return Err(GenomeError::Memory(memory));
+        }
+        if genes.len() != Self::length(memory) {
+            return Err(GenomeError::Length(genes.len()));
+        }
+        let genes = genes
+            .iter()
+            .enumerate()
+            .filter(|(_, gene)| **gene == Move::Defect)
+            .fold(0, |bits, (i, _)| bits | 1 << i);
+        Ok(Self {
+            memory: memory as u8,
+            genes,
+        })
+    }
+
+    //every gene picked by a coin flip
+    pub fn random(memory: usize, rng: &mut impl Rng) -> Result<Self, GenomeError> {
+        let genes: Vec<_> = (0..Self::length(memory.min(MAX_MEMORY)))
+            .map(|_| {
+                if rng.random_bool(0.5) {
+                    Move::Defect
+                } else {
+                    Move::Cooperate
+                }
+            })
+            .collect();
+        Self::new(memory, &genes)
+    }
+
+    //tit for tat as a genome, cooperating first and then copying the opponent's last move
+    pub fn tit_for_tat(memory: usize) -> Result<Self, GenomeError> {
+        let genes: Vec<_> = (0..Self::length(memory.min(MAX_MEMORY)))
+            .map(|i| {
+                //in the table the opponent's most recent move is the lowest bit of the index
+                if i >= memory && (i - memory) % 2 == 1 {
+                    Move::Defect
+                } else {
+                    Move::Cooperate
+                }
+            })
+            .collect();
+        Self::new(memory, &genes)
+    }
+
+    pub fn memory(&self) -> usize {
+        self.memory as usize
+    }
+
+    //how many genes this genome has
+    pub fn size(&self) -> usize {
+        Self::length(self.memory())
+    }
+
+    pub fn gene(&self, i: usize) -> Move {
+        if self.genes & 1 << i == 0 {
+            Move::Cooperate
+        } else {
+            Move::Defect
+        }
+    }
+
+    pub fn genes(&self) -> impl Iterator<Item = Move> + '_ {
+        (0..self.size()).map(|i| self.gene(i))
+    }
+
+    //looks up the move to play, the oldest remembered round is the highest part of the index
+    pub fn decide(&self, history: Option<&History>) -> Move {
+        let memory = self.memory();
+        let known = history.map_or(0, |h| h.len().min(memory));
+        let Some(history) = history.filter(|_| known == memory) else {
+            return self.gene(known);
+        };
+        let bit = |m: Move| usize::from(m == Move::Defect);
+        let index = (0..memory)
+            .rev()
+            .filter_map(|n| history.nth_last(n))
+            .fold(0, |index, round| {
+                index * 4 + bit(round.own) * 2 + bit(round.opponent)
+            });
+        self.gene(memory + index)
+    }
+
+    //single point crossover, the genes before a random cut come from this genome and the rest
+    //from the other, genomes of different memory can't be crossed and come back unchanged
+    pub fn crossover(&self, other: &Genome, rng: &mut impl Rng) -> Genome {
+        if self.memory != other.memory {
+            return *self;
+        }
+        let cut = rng.random_range(1..self.size());
+        let low = (1 << cut) - 1;
+        Genome {
+            memory: self.memory,
+            genes: self.genes & low | other.genes & !low,
+        }
+    }
+
+    //flips every gene with the given chance, nothing is drawn when the chance is 0
+    pub fn mutate(&self, rate: f64, rng: &mut impl Rng) -> Genome {
+        if rate <= 0.0 {
+            return *self;
+        }
+        let flips = (0..self.size())
+            .filter(|_| rng.random_bool(rate.min(1.0)))
+            .fold(0, |bits, i| bits | 1 << i);
+        Genome {
+            memory: self.memory,
+            genes: self.genes ^ flips,
+        }
+    }
+
+    //the share of genes that match another genome of the same memory
+    pub fn agreement(&self, other: &Genome) -> f64 {
+        if self.memory != other.memory {
+            return 0.0;
+        }
+        let differing = (self.genes ^ other.genes).count_ones() as usize;
+        1.0 - differing as f64 / self.size() as f64
+    }
+}
+
+impl fmt::Display for Genome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for gene in self.genes() {
+            f.write_str(match gene {
+                Move::Cooperate => "C",
+                Move::Defect => "D",
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Genome {
+    type Err = GenomeError;
+
+    //the memory is worked out from the length
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let genes = text
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'C' => Ok(Move::Cooperate),
+                'D' => Ok(Move::Defect),
+                _ => Err(GenomeError::Gene(c)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let memory = (1..=MAX_MEMORY)
+            .find(|memory| Self::length(*memory) == genes.len())
+            .ok_or(GenomeError::Length(genes.len()))?;
+        Self::new(memory, &genes)
+    }
+}
+
+impl TryFrom<String> for Genome {
+    type Error = GenomeError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Genome> for String {
+    fn from(genome: Genome) -> Self {
+        genome.to_string()
+    }
+}
+
+impl fmt::Display for GenomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenomeError::Memory(memory) => {
+                write!(f, "genomes remember 1 to {MAX_MEMORY} rounds, not {memory}")
+            }
+            GenomeError::Length(length) => write!(
+                f,
+                "a genome has {} or {} genes, not {length}",
+                Genome::length(1),
+                Genome::length(2)
+            ),
+            GenomeError::Gene(c) => write!(f, "genes are C or D, not {c}"),
+        }
+    }
+}
+
+impl std::error::Error for GenomeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    fn history(rounds: &[(Move, Move)]) -> History {
+        let mut history = History::default();
+        for (own, opponent) in rounds {
+            history.record(*own, *opponent);
+        }
+        history
+    }
+
+    #[test]
+    fn genomes_round_trip_through_strings() {
+        let tit_for_tat = Genome::tit_for_tat(1).unwrap();
+        assert_eq!(tit_for_tat.to_string(), "CCDCD");
+        assert_eq!("ccdcd".parse::<Genome>(), Ok(tit_for_tat));
+        let memory_two = Genome::tit_for_tat(2).unwrap();
+        assert_eq!(memory_two.to_string().parse::<Genome>(), Ok(memory_two));
+        assert_eq!(memory_two.memory(), 2);
+        assert_eq!("CCDC".parse::<Genome>(), Err(GenomeError::Length(4)));
+        assert_eq!("CCXCD".parse::<Genome>(), Err(GenomeError::Gene('X')));
+    }
+
+    #[test]
+    fn the_oldest_round_is_the_highest_part_of_the_index() {
+        use Move::{Cooperate as C, Defect as D};
+        //defects on its opening move and after (C, D) then (D, C), index 1 * 4 + 2
+        let genome: Genome = "DCCCCCCCDCCCCCCCCC".parse().unwrap();
+        assert_eq!(genome.decide(None), D);
+        assert_eq!(genome.decide(Some(&history(&[(C, D)]))), C);
+        assert_eq!(genome.decide(Some(&history(&[(C, D), (D, C)]))), D);
+        assert_eq!(genome.decide(Some(&history(&[(D, C), (C, D)]))), C);
+        //only the last two rounds count
+        assert_eq!(genome.decide(Some(&history(&[(D, D), (C, D), (D, C)]))), D);
+
+        let tit_for_tat = Genome::tit_for_tat(1).unwrap();
+        for (own, opponent) in [(C, C), (C, D), (D, C), (D, D)] {
+            let history = history(&[(own, opponent)]);
+            assert_eq!(tit_for_tat.decide(Some(&history)), opponent);
+        }
+    }
+
+    #[test]
+    fn crossover_cuts_between_two_genes() {
+        let first: Genome = "CCCCC".parse().unwrap();
+        let second: Genome = "DDDDD".parse().unwrap();
+        let mut rng = rng::seeded(3);
+        for _ in 0..50 {
+            let child = first.crossover(&second, &mut rng).to_string();
+            let cut = child.find('D').unwrap();
+            assert!((1..5).contains(&cut), "{child}");
+            assert!(child[cut..].chars().all(|gene| gene == 'D'), "{child}");
+        }
+        //genomes of different memory aren't crossed
+        let other = Genome::tit_for_tat(2).unwrap();
+        assert_eq!(first.crossover(&other, &mut rng), first);
+    }
+
+    #[test]
+    fn mutation_flips_genes_with_its_rate() {
+        let genome = Genome::tit_for_tat(2).unwrap();
+        let mut rng = rng::seeded(5);
+        assert_eq!(genome.mutate(0.0, &mut rng), genome);
+        let flipped = genome.mutate(1.0, &mut rng);
+        assert!(genome.genes().zip(flipped.genes()).all(|(a, b)| a != b));
+        assert_eq!(genome.agreement(&flipped), 0.0);
+        let some = genome.mutate(0.5, &mut rng);
+        assert_eq!(some.memory(), 2);
+        assert_ne!(some, genome);
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use crate::{
+    arena::Arena,
+    outcome::{EndConditions, EndReason, RunSummary},
+    public_goods::Interaction,
+    replay::ReplayRecorder,
+    reproduction::Reproduction,
+    scenario::{Scenario, ScenarioError},
+    simulation::Simulation,
+    stats::TimeSeries,
+    strategy::StrategyRegistry,
+};
+
+//a run in the kinematic arena, as fast as it can go and with nothing to draw
+#[derive(Debug)]
+pub struct HeadlessRun {
+    pub simulation: Simulation,
+    pub arena: Arena,
+    pub end_conditions: EndConditions,
+    pub reproduction: Reproduction,
+    pub interaction: Interaction,
+    //records a replay of the run when set
+    pub recorder: Option<ReplayRecorder>,
+    ended: Option<EndReason>,
+}
+
+impl HeadlessRun {
+    //spawns the scenario's population with the given seed
+    pub fn new(
+        scenario: &Scenario,
+        seed: u64,
+        strategies: Arc<StrategyRegistry>,
+    ) -> Result<Self, ScenarioError> {
+        let spawn_counts = scenario.spawn_counts(&strategies)?;
+        let mut simulation = Simulation::new(seed, scenario.payoffs(), strategies);
+        simulation.stats = TimeSeries::new(scenario.sample_interval);
+        simulation.population.noise = scenario.noise;
+        let mut arena = Arena::default();
+        for (strategy, count) in spawn_counts {
+            for _ in 0..count {
+                arena.spawn(&mut simulation, strategy, scenario.healthpoints);
+            }
+        }
+        //the starting population is the first sample
+        simulation.finish_tick();
+        Ok(Self {
+            simulation,
+            arena,
+            end_conditions: scenario.end_conditions,
+            reproduction: scenario.reproduction,
+            interaction: scenario.interaction.clone(),
+            recorder: None,
+            ended: None,
+        })
+    }
+
+    //starts recording a replay from the current tick
+    pub fn record_replay(&mut self) {
+        let mut recorder = ReplayRecorder::new(&self.simulation);
+        recorder.record_tick(&self.simulation, &[], &[], &self.arena.positions());
+        self.recorder = Some(recorder);
+    }
+
+    //runs a single tick, returning why the run ended if it just did
+    pub fn tick(&mut self) -> Option<EndReason> {
+        if self.ended.is_some() {
+            return self.ended;
+        }
+        self.simulation.clock.advance();
+        let encounters = self.arena.tick(&mut self.simulation, &self.interaction);
+        let gatherings = match self.interaction.public_goods() {
+            Some(public_goods) => self
+                .simulation
+                .gather(public_goods, &self.arena.positions()),
+            None => Vec::new(),
+        };
+        self.arena
+            .reproduce(&mut self.simulation, &self.reproduction);
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record_tick(
+                &self.simulation,
+                &encounters,
+                &gatherings,
+                &self.arena.positions(),
+            );
+        }
+        self.simulation.finish_tick();
+        self.ended = self.end_conditions.check(&self.simulation);
+        self.ended
+    }
+
+    //runs until an end condition is met or `max_ticks` more ticks have passed
+    pub fn run(&mut self, max_ticks: u64) -> Option<EndReason> {
+        for _ in 0..max_ticks {
+            if self.tick().is_some() {
+                break;
+            }
+        }
+        self.simulation.final_sample();
+        self.ended
+    }
+
+    pub fn ended(&self) -> Option<EndReason> {
+        self.ended
+    }
+
+    pub fn summary(&self) -> RunSummary {
+        let mut summary = RunSummary::new(&self.simulation, self.ended);
+        summary.public_goods = self.interaction.public_goods().cloned();
+        summary
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    //a small crowded run that splits and mutates early, quick enough for a debug build
+    pub(crate) fn scenario() -> Scenario {
+        Scenario::from_ron(
+            r#"(
+                spawn: { "Coop": 6, "Greed": 6, "TFT": 6, "Pavlov": 6 },
+                healthpoints: 10,
+                end_conditions: (one_strategy_left: false, all_dead: true),
+                reproduction: (threshold: Some(12), capacity: Some(40), mutation: 0.1),
+                noise: (execution: 0.05, perception: 0.05),
+            )"#,
+        )
+        .unwrap()
+    }
+
+    fn run(seed: u64, ticks: u64) -> HeadlessRun {
+        let mut run = HeadlessRun::new(&scenario(), seed, Arc::default()).unwrap();
+        run.run(ticks);
+        run
+    }
+
+    fn events_json(run: &HeadlessRun) -> String {
+        serde_json::to_string(run.simulation.events.events()).unwrap()
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        let (a, b) = (run(3, 1200), run(3, 1200));
+        assert!(a.simulation.lineage.len() > 24, "nothing was born");
+        assert_eq!(a.summary(), b.summary());
+        assert_eq!(events_json(&a), events_json(&b));
+        assert_eq!(a.simulation.lineage, b.simulation.lineage);
+    }
+
+    #[test]
+    fn other_seed_other_run() {
+        assert_ne!(events_json(&run(3, 1200)), events_json(&run(4, 1200)));
+    }
+}
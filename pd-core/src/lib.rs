@@ -0,0 +1,57 @@
+//! Engine-independent simulation core.
+//!
+//! Owns the agents, strategies, payoffs and encounter rules of the simulator as plain Rust
+//! types, so a run can be driven from the Fyrox game, a test or a batch job alike.
+
+pub mod agent;
+pub mod arena;
+pub mod clock;
+pub mod encounter;
+pub mod events;
+pub mod evolution;
+pub mod genome;
+pub mod headless;
+pub mod lineage;
+pub mod memory;
+pub mod noise;
+pub mod outcome;
+pub mod payoff;
+pub mod population;
+pub mod public_goods;
+pub mod replay;
+pub mod reproduction;
+pub mod rng;
+pub mod scenario;
+pub mod simulation;
+pub mod snapshot;
+pub mod stats;
+pub mod strategies;
+pub mod strategy;
+pub mod sweep;
+pub mod tournament;
+
+pub use agent::{Agent, AgentId};
+pub use arena::Arena;
+pub use clock::SimClock;
+pub use encounter::{Encounter, Side};
+pub use events::{Event, EventBus};
+pub use evolution::{Evolution, EvolutionResult, GenerationStats};
+pub use genome::{Genome, GenomeError};
+pub use headless::HeadlessRun;
+pub use lineage::{Lineage, LineageNode};
+pub use memory::{History, Memory, Round};
+pub use noise::Noise;
+pub use outcome::{EndConditions, EndReason, RunSummary, StrategySummary};
+pub use payoff::{Condition, GameType, PayoffError, PayoffMatrix};
+pub use population::Population;
+pub use public_goods::{Gathering, Interaction, PublicGoods, Zone};
+pub use replay::{Replay, ReplayPlayer, ReplayRecorder};
+pub use reproduction::{Birth, Reproduction, ReproductionError};
+pub use rng::SimRng;
+pub use scenario::{Scenario, ScenarioError};
+pub use simulation::Simulation;
+pub use snapshot::{SimulationState, Snapshot, SnapshotError};
+pub use stats::{Record, Sample, TimeSeries};
+pub use strategy::{Move, Strategy, StrategyId, StrategyRegistry};
+pub use sweep::{Cell, CellStats, Estimate, SpawnAxis, Sweep};
+pub use tournament::{Standing, Tournament, TournamentResult};
@@ -0,0 +1,288 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    agent::{Agent, AgentId},
+    genome::Genome,
+    stats::csv_field,
+    strategy::{StrategyId, StrategyRegistry},
+};
+
+//one agent in the family tree, kept after it dies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineageNode {
+    pub id: AgentId,
+    pub parent: Option<AgentId>,
+    pub strategy: StrategyId,
+    pub generation: u32,
+    pub born: u64,
+    //none while the agent is alive
+    pub died: Option<u64>,
+    #[serde(default)]
+    pub genome: Option<Genome>,
+}
+
+//every agent that ever lived in a run and who it split off from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lineage {
+    nodes: BTreeMap<AgentId, LineageNode>,
+}
+
+impl LineageNode {
+    pub fn of(agent: &Agent) -> Self {
+        Self {
+            id: agent.id,
+            parent: agent.parent,
+            strategy: agent.strategy,
+            generation: agent.generation,
+            born: agent.born,
+            died: None,
+            genome: agent.genome,
+        }
+    }
+}
+
+impl Lineage {
+    pub fn record_birth(&mut self, agent: &Agent) {
+        self.nodes.insert(agent.id, LineageNode::of(agent));
+    }
+
+    pub fn record_death(&mut self, agent: AgentId, tick: u64) {
+        if let Some(node) = self.nodes.get_mut(&agent) {
+            node.died = Some(tick);
+        }
+    }
+
+    pub fn get(&self, agent: AgentId) -> Option<&LineageNode> {
+        self.nodes.get(&agent)
+    }
+
+    //every agent in id order, parents always come before their offspring
+    pub fn nodes(&self) -> impl Iterator<Item = &LineageNode> {
+        self.nodes.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    //the founder every agent descends from, founders are their own
+    pub fn founders(&self) -> BTreeMap<AgentId, AgentId> {
+        let mut founders = BTreeMap::new();
+        for node in self.nodes() {
+            let founder = node
+                .parent
+                .and_then(|parent| founders.get(&parent).copied())
+                .unwrap_or(node.id);
+            founders.insert(node.id, founder);
+        }
+        founders
+    }
+
+    //writes the tree in newick, nodes are labelled `<strategy>_<id>` and branch lengths are the
+    //ticks between a parent's birth and its offspring's, founders hang off one shared root
+    pub fn write_newick(
+        &self,
+        writer: &mut impl Write,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        let mut children: BTreeMap<AgentId, Vec<AgentId>> = BTreeMap::new();
+        let mut roots = Vec::new();
+        for node in self.nodes() {
+            match node.parent.filter(|parent| self.nodes.contains_key(parent)) {
+                Some(parent) => children.entry(parent).or_default().push(node.id),
+                None => roots.push(node.id),
+            }
+        }
+
+        write!(writer, "(")?;
+        for (i, root) in roots.iter().enumerate() {
+            if i > 0 {
+                write!(writer, ",")?;
+            }
+            self.write_subtree(writer, *root, &children, strategies)?;
+        }
+        writeln!(writer, ");")
+    }
+
+    //writes one agent and everyone descended from it, walking the tree without recursion since
+    //a long run can be thousands of generations deep
+    fn write_subtree(
+        &self,
+        writer: &mut impl Write,
+        root: AgentId,
+        children: &BTreeMap<AgentId, Vec<AgentId>>,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        //each entry is an agent and how many of its offspring have been written so far
+        let mut stack = vec![(root, 0)];
+        while let Some((agent, written)) = stack.pop() {
+            let offspring = children.get(&agent).map_or(&[][..], Vec::as_slice);
+            if written < offspring.len() {
+                write!(writer, "{}", if written == 0 { "(" } else { "," })?;
+                stack.push((agent, written + 1));
+                stack.push((offspring[written], 0));
+                continue;
+            }
+            if !offspring.is_empty() {
+                write!(writer, ")")?;
+            }
+            let Some(node) = self.nodes.get(&agent) else {
+                continue;
+            };
+            let strategy = strategies
+                .get(node.strategy)
+                .map_or_else(|| node.strategy.to_string(), |s| s.name().to_owned());
+            write!(writer, "{}", newick_label(&format!("{strategy}_{agent}")))?;
+            if let Some(parent) = node.parent.and_then(|parent| self.nodes.get(&parent)) {
+                write!(writer, ":{}", node.born.saturating_sub(parent.born))?;
+            }
+        }
+        Ok(())
+    }
+
+    //writes one row per agent, the edge from its parent, founders have no parent
+    pub fn write_csv(
+        &self,
+        writer: &mut impl Write,
+        seed: u64,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        let name = |strategy: StrategyId| {
+            strategies
+                .get(strategy)
+                .map_or_else(|| strategy.to_string(), |s| s.name().to_owned())
+        };
+        let founders = self.founders();
+        writeln!(
+            writer,
+            "seed,parent,agent,founder,strategy,parent_strategy,mutated,generation,born,died,genome"
+        )?;
+        for node in self.nodes() {
+            let parent = node.parent.and_then(|parent| self.nodes.get(&parent));
+            writeln!(
+                writer,
+                "{seed},{},{},{},{},{},{},{},{},{},{}",
+                node.parent.map_or_else(String::new, |p| p.to_string()),
+                node.id,
+                founders[&node.id],
+                csv_field(&name(node.strategy)),
+                parent.map_or_else(String::new, |p| csv_field(&name(p.strategy))),
+                parent.is_some_and(|p| p.strategy != node.strategy),
+                node.generation,
+                node.born,
+                node.died.map_or_else(String::new, |tick| tick.to_string()),
+                node.genome
+                    .map_or_else(String::new, |genome| genome.to_string())
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn export_newick(
+        &self,
+        path: impl AsRef<Path>,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_newick(&mut file, strategies)?;
+        file.flush()
+    }
+
+    pub fn export_csv(
+        &self,
+        path: impl AsRef<Path>,
+        seed: u64,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut file, seed, strategies)?;
+        file.flush()
+    }
+}
+
+//quotes a label that has any of the characters newick gives a meaning to
+fn newick_label(label: &str) -> String {
+    if label
+        .chars()
+        .any(|c| c.is_whitespace() || "()[]':;,".contains(c))
+    {
+        format!("'{}'", label.replace('\'', "''"))
+    } else {
+        label.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //two Coop generations under founder 0 and a lone Greed founder 1
+    fn family() -> Lineage {
+        let mut lineage = Lineage::default();
+        let agents = [
+            (0, 0, None, 0),
+            (1, 1, None, 0),
+            (2, 0, Some(0), 5),
+            (3, 0, Some(2), 12),
+        ];
+        for (id, strategy, parent, born) in agents {
+            let mut agent = Agent::new(id, strategy, 10);
+            agent.parent = parent;
+            agent.generation = parent.map_or(0, |p| lineage.get(p).unwrap().generation + 1);
+            agent.born = born;
+            lineage.record_birth(&agent);
+        }
+        lineage.record_death(1, 30);
+        lineage
+    }
+
+    #[test]
+    fn writes_newick() {
+        let mut out = Vec::new();
+        family()
+            .write_newick(&mut out, &StrategyRegistry::default())
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "(((Coop_3:7)Coop_2:5)Coop_0,Greed_1);\n"
+        );
+    }
+
+    #[test]
+    fn quotes_newick_labels() {
+        assert_eq!(newick_label("TFT_4"), "TFT_4");
+        assert_eq!(newick_label("Mostly Coop_4"), "'Mostly Coop_4'");
+        assert_eq!(newick_label("it's_4"), "'it''s_4'");
+    }
+
+    #[test]
+    fn writes_csv() {
+        let mut out = Vec::new();
+        family()
+            .write_csv(&mut out, 7, &StrategyRegistry::default())
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "seed,parent,agent,founder,strategy,parent_strategy,mutated,generation,born,died,genome",
+                "7,,0,0,Coop,,false,0,0,,",
+                "7,,1,1,Greed,,false,0,0,30,",
+                "7,0,2,0,Coop,Coop,false,1,5,,",
+                "7,2,3,0,Coop,Coop,false,2,12,,",
+            ]
+        );
+    }
+}
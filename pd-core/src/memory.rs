@@ -0,0 +1,90 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{agent::AgentId, strategy::Move};
+
+//how many past rounds are kept against each opponent
+pub const MEMORY_LENGTH: usize = 8;
+
+//the moves both sides played in one encounter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Round {
+    pub own: Move,
+    pub opponent: Move,
+}
+
+//what an agent remembers about a single opponent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    rounds: VecDeque<Round>,
+    opponent_defected: bool,
+}
+
+impl History {
+    pub fn record(&mut self, own: Move, opponent: Move) {
+        if self.rounds.len() == MEMORY_LENGTH {
+            self.rounds.pop_front();
+        }
+        self.rounds.push_back(Round { own, opponent });
+        self.opponent_defected |= opponent == Move::Defect;
+    }
+
+    //the most recent round, if any
+    pub fn last(&self) -> Option<Round> {
+        self.rounds.back().copied()
+    }
+
+    //the round `n` encounters ago, 0 being the most recent
+    pub fn nth_last(&self, n: usize) -> Option<Round> {
+        self.rounds.iter().rev().nth(n).copied()
+    }
+
+    //true once the opponent has defected at least once, even if it was forgotten since
+    pub fn opponent_ever_defected(&self) -> bool {
+        self.opponent_defected
+    }
+
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+}
+
+//the histories an agent keeps, one per opponent it has met
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Memory {
+    opponents: HashMap<AgentId, History>,
+    //the public goods games it played, with the group standing in for the opponent
+    #[serde(default)]
+    group: History,
+}
+
+impl Memory {
+    pub fn history(&self, opponent: AgentId) -> Option<&History> {
+        self.opponents.get(&opponent)
+    }
+
+    pub fn record(&mut self, opponent: AgentId, own: Move, theirs: Move) {
+        self.opponents
+            .entry(opponent)
+            .or_default()
+            .record(own, theirs);
+    }
+
+    pub fn group(&self) -> Option<&History> {
+        Some(&self.group).filter(|group| !group.is_empty())
+    }
+
+    //`theirs` is what most of the others in the group played
+    pub fn record_group(&mut self, own: Move, theirs: Move) {
+        self.group.record(own, theirs);
+    }
+
+    pub fn forget(&mut self, opponent: AgentId) {
+        self.opponents.remove(&opponent);
+    }
+}
@@ -0,0 +1,101 @@
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::Move;
+
+//trembling hands and blurry eyes, the chances an agent gets a move wrong
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Noise {
+    //the chance an agent plays the opposite of the move it picked
+    pub execution: f64,
+    //the chance an agent remembers the opposite of the move its opponent played
+    pub perception: f64,
+}
+
+impl Noise {
+    //the move actually played when `intended` was picked
+    pub fn execute(&self, intended: Move, rng: &mut dyn RngCore) -> Move {
+        flip(intended, self.execution, rng)
+    }
+
+    //the move remembered when the opponent played `seen`
+    pub fn perceive(&self, seen: Move, rng: &mut dyn RngCore) -> Move {
+        flip(seen, self.perception, rng)
+    }
+}
+
+//nothing is drawn when the chance is 0 so noiseless runs play out the same as before
+fn flip(own: Move, chance: f64, rng: &mut dyn RngCore) -> Move {
+    if chance > 0.0 && rng.random_bool(chance.min(1.0)) {
+        own.opposite()
+    } else {
+        own
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng, simulation::Simulation};
+
+    const MOVES: [Move; 2] = [Move::Cooperate, Move::Defect];
+
+    #[test]
+    fn no_noise_never_flips_or_draws() {
+        let noise = Noise::default();
+        let mut rng = rng::seeded(1);
+        for _ in 0..100 {
+            for own in MOVES {
+                assert_eq!(noise.execute(own, &mut rng), own);
+                assert_eq!(noise.perceive(own, &mut rng), own);
+            }
+        }
+        assert_eq!(rng.next_u64(), rng::seeded(1).next_u64());
+    }
+
+    #[test]
+    fn certain_noise_always_flips() {
+        let noise = Noise {
+            execution: 1.0,
+            perception: 1.0,
+        };
+        let mut rng = rng::seeded(2);
+        for _ in 0..100 {
+            for own in MOVES {
+                assert_eq!(noise.execute(own, &mut rng), own.opposite());
+                assert_eq!(noise.perceive(own, &mut rng), own.opposite());
+            }
+        }
+        //chances above 1 count as 1
+        let over = Noise {
+            execution: 2.0,
+            perception: 0.0,
+        };
+        assert_eq!(over.execute(Move::Cooperate, &mut rng), Move::Defect);
+    }
+
+    #[test]
+    fn misperceiving_leaves_the_played_move_alone() {
+        let mut simulation = Simulation::default();
+        simulation.population.noise = Noise {
+            execution: 0.0,
+            perception: 1.0,
+        };
+        let coop = simulation.spawn(0, 10);
+        let greed = simulation.spawn(1, 10);
+        simulation.clock.advance();
+        let encounter = simulation.resolve(&[(coop, greed)])[0];
+        assert_eq!(encounter.a.played, Move::Cooperate);
+        assert_eq!(encounter.b.played, Move::Defect);
+        assert_eq!((encounter.a.payoff, encounter.b.payoff), (-2, 3));
+        //only the memories are wrong
+        let remembered = |agent, opponent| {
+            let agent = simulation.population.get(agent).unwrap();
+            agent.memory.history(opponent).unwrap().last().unwrap()
+        };
+        assert_eq!(remembered(coop, greed).opponent, Move::Cooperate);
+        assert_eq!(remembered(coop, greed).own, Move::Cooperate);
+        assert_eq!(remembered(greed, coop).opponent, Move::Defect);
+    }
+}
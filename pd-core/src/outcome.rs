@@ -0,0 +1,345 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    payoff::{GameType, PayoffMatrix},
+    public_goods::PublicGoods,
+    simulation::Simulation,
+    strategy::StrategyId,
+};
+
+//when a run should stop on its own, every condition is checked after each tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndConditions {
+    //stop once only one of the strategies the run started with is left
+    pub one_strategy_left: bool,
+    //stop once every agent is dead
+    pub all_dead: bool,
+    //stop after this many ticks
+    pub tick_limit: Option<u64>,
+    //stop once no strategy gained or lost an agent for this many ticks
+    pub stable_for: Option<u64>,
+}
+
+impl Default for EndConditions {
+    fn default() -> Self {
+        Self {
+            one_strategy_left: true,
+            all_dead: true,
+            tick_limit: None,
+            stable_for: None,
+        }
+    }
+}
+
+//why a run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum EndReason {
+    OneStrategyLeft { strategy: StrategyId },
+    AllDead,
+    TickLimit,
+    Stable,
+}
+
+impl fmt::Display for EndReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EndReason::OneStrategyLeft { .. } => write!(f, "only one strategy left"),
+            EndReason::AllDead => write!(f, "every bugster died"),
+            EndReason::TickLimit => write!(f, "tick limit reached"),
+            EndReason::Stable => write!(f, "population stable"),
+        }
+    }
+}
+
+impl EndConditions {
+    //the first condition the simulation meets, if any
+    pub fn check(&self, simulation: &Simulation) -> Option<EndReason> {
+        let tick = simulation.clock.tick();
+        let population = &simulation.population;
+        let stats = &simulation.stats;
+
+        if self.all_dead && population.is_empty() {
+            return Some(EndReason::AllDead);
+        }
+        if self.one_strategy_left {
+            //strategies that never had an agent don't count as extinct
+            let present: Vec<_> = population
+                .strategies
+                .ids()
+                .filter(|s| stats.record(*s).peak_alive > 0)
+                .collect();
+            let alive: Vec<_> = present
+                .iter()
+                .copied()
+                .filter(|s| population.count(*s) > 0)
+                .collect();
+            if present.len() > 1 && alive.len() == 1 {
+                return Some(EndReason::OneStrategyLeft { strategy: alive[0] });
+            }
+        }
+        if self.tick_limit.is_some_and(|limit| tick >= limit) {
+            return Some(EndReason::TickLimit);
+        }
+        if self
+            .stable_for
+            .is_some_and(|ticks| tick.saturating_sub(stats.last_change()) >= ticks)
+        {
+            return Some(EndReason::Stable);
+        }
+        None
+    }
+}
+
+//how one strategy did over a run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategySummary {
+    pub strategy: String,
+    pub alive: usize,
+    pub total_hp: i64,
+    pub peak_alive: usize,
+    pub peak_tick: u64,
+    pub extinct_at: Option<u64>,
+}
+
+//the outcome of a finished run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunSummary {
+    pub seed: u64,
+    //the game the payoffs make
+    pub game: GameType,
+    pub payoffs: PayoffMatrix,
+    //set when the agents played public goods games instead of the two player game
+    #[serde(default)]
+    pub public_goods: Option<PublicGoods>,
+    pub ticks: u64,
+    pub reason: Option<EndReason>,
+    pub winner: Option<String>,
+    pub strategies: Vec<StrategySummary>,
+}
+
+impl RunSummary {
+    //sums up the run so far, the winner is the last strategy standing, otherwise the one
+    //with the most agents alive and then the most health
+    pub fn new(simulation: &Simulation, reason: Option<EndReason>) -> Self {
+        let population = &simulation.population;
+        let registry = &population.strategies;
+        let name = |id: StrategyId| {
+            registry
+                .get(id)
+                .map_or_else(|| id.to_string(), |s| s.name().to_owned())
+        };
+
+        let winner = match reason {
+            Some(EndReason::OneStrategyLeft { strategy }) => Some(strategy),
+            _ => registry
+                .ids()
+                .filter(|s| population.count(*s) > 0)
+                .max_by_key(|s| (population.count(*s), population.total_hp(*s))),
+        };
+
+        Self {
+            seed: simulation.seed,
+            game: GameType::of(&population.payoffs),
+            payoffs: population.payoffs,
+            public_goods: None,
+            ticks: simulation.clock.tick(),
+            reason,
+            winner: winner.map(name),
+            //only the strategies that took part in the run
+            strategies: registry
+                .ids()
+                .filter(|id| simulation.stats.record(*id).peak_alive > 0)
+                .map(|id| {
+                    let record = simulation.stats.record(id);
+                    StrategySummary {
+                        strategy: name(id),
+                        alive: population.count(id),
+                        total_hp: population.total_hp(id),
+                        peak_alive: record.peak_alive,
+                        peak_tick: record.peak_tick,
+                        extinct_at: record.extinct_at,
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn export_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, self)?;
+        file.flush()
+    }
+}
+
+impl fmt::Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.reason {
+            Some(reason) => writeln!(f, "Run ended at tick {}: {reason}", self.ticks)?,
+            None => writeln!(f, "Run stopped at tick {}", self.ticks)?,
+        }
+        writeln!(f, "Winner: {}", self.winner.as_deref().unwrap_or("none"))?;
+        match &self.public_goods {
+            Some(public_goods) => writeln!(
+                f,
+                "Game: Public Goods (stake = {}, r = {}, {} zones)",
+                public_goods.stake,
+                public_goods.multiplier,
+                public_goods.zones.len()
+            )?,
+            None => writeln!(
+                f,
+                "Game: {} (T = {}, R = {}, P = {}, S = {})",
+                self.game,
+                self.payoffs.temptation,
+                self.payoffs.reward,
+                self.payoffs.punishment,
+                self.payoffs.sucker
+            )?,
+        }
+        writeln!(f, "Seed: {}", self.seed)?;
+        for s in &self.strategies {
+            write!(
+                f,
+                "{}: {} alive, {} HP, peak {} at tick {}",
+                s.strategy, s.alive, s.total_hp, s.peak_alive, s.peak_tick
+            )?;
+            if let Some(tick) = s.extinct_at {
+                write!(f, ", extinct at tick {tick}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::AgentId;
+
+    const NONE: EndConditions = EndConditions {
+        one_strategy_left: false,
+        all_dead: false,
+        tick_limit: None,
+        stable_for: None,
+    };
+
+    //a simulation with one Coop and one Greed agent, tracked at tick 0
+    fn coop_and_greed() -> (Simulation, AgentId, AgentId) {
+        let mut simulation = Simulation::default();
+        let registry = simulation.population.strategies.clone();
+        let coop = simulation.spawn(registry.find("Coop").unwrap(), 10);
+        let greed = simulation.spawn(registry.find("Greed").unwrap(), 10);
+        simulation.finish_tick();
+        (simulation, coop, greed)
+    }
+
+    fn run_to(simulation: &mut Simulation, tick: u64) {
+        while simulation.clock.tick() < tick {
+            simulation.clock.advance();
+            simulation.finish_tick();
+        }
+    }
+
+    #[test]
+    fn all_dead() {
+        let (mut simulation, coop, greed) = coop_and_greed();
+        let conditions = EndConditions {
+            all_dead: true,
+            ..NONE
+        };
+        assert_eq!(conditions.check(&simulation), None);
+        simulation.population.remove(coop);
+        simulation.population.remove(greed);
+        assert_eq!(conditions.check(&simulation), Some(EndReason::AllDead));
+        assert_eq!(NONE.check(&simulation), None);
+    }
+
+    #[test]
+    fn one_strategy_left() {
+        let (mut simulation, coop, greed) = coop_and_greed();
+        let conditions = EndConditions {
+            one_strategy_left: true,
+            ..NONE
+        };
+        let strategy = simulation.population.get(coop).unwrap().strategy;
+        simulation.population.remove(greed);
+        assert_eq!(
+            conditions.check(&simulation),
+            Some(EndReason::OneStrategyLeft { strategy })
+        );
+        //but not once every strategy is gone
+        simulation.population.remove(coop);
+        assert_eq!(conditions.check(&simulation), None);
+
+        //a run that starts with one strategy doesn't end right away
+        let mut simulation = Simulation::default();
+        simulation.spawn(strategy, 10);
+        simulation.finish_tick();
+        assert_eq!(conditions.check(&simulation), None);
+    }
+
+    #[test]
+    fn tick_limit() {
+        let (mut simulation, _, _) = coop_and_greed();
+        let conditions = EndConditions {
+            tick_limit: Some(5),
+            ..NONE
+        };
+        run_to(&mut simulation, 4);
+        assert_eq!(conditions.check(&simulation), None);
+        run_to(&mut simulation, 5);
+        assert_eq!(conditions.check(&simulation), Some(EndReason::TickLimit));
+    }
+
+    #[test]
+    fn stable_for() {
+        let (mut simulation, coop, _) = coop_and_greed();
+        let conditions = EndConditions {
+            stable_for: Some(10),
+            ..NONE
+        };
+        run_to(&mut simulation, 9);
+        assert_eq!(conditions.check(&simulation), None);
+        run_to(&mut simulation, 10);
+        assert_eq!(conditions.check(&simulation), Some(EndReason::Stable));
+        //a death starts the count again
+        simulation.population.remove(coop);
+        run_to(&mut simulation, 11);
+        assert_eq!(conditions.check(&simulation), None);
+        run_to(&mut simulation, 21);
+        assert_eq!(conditions.check(&simulation), Some(EndReason::Stable));
+    }
+
+    #[test]
+    fn conditions_are_checked_in_order() {
+        let conditions = EndConditions {
+            one_strategy_left: true,
+            all_dead: true,
+            tick_limit: Some(10),
+            stable_for: Some(10),
+        };
+        //the tick limit and stability both hold, the tick limit is reported
+        let (mut simulation, coop, greed) = coop_and_greed();
+        run_to(&mut simulation, 10);
+        assert_eq!(conditions.check(&simulation), Some(EndReason::TickLimit));
+        //a strategy winning comes before either
+        let strategy = simulation.population.get(greed).unwrap().strategy;
+        simulation.population.remove(coop);
+        assert_eq!(
+            conditions.check(&simulation),
+            Some(EndReason::OneStrategyLeft { strategy })
+        );
+        //and everyone dying before that
+        simulation.population.remove(greed);
+        assert_eq!(conditions.check(&simulation), Some(EndReason::AllDead));
+    }
+}
@@ -0,0 +1,385 @@
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::Move;
+
+//our values to calcuate health gain
+const GREEDGREED_HEALTH_GAIN: i64 = -1;
+const GREEDCOOP_HEALTH_GAIN: i64 = 3;
+const COOPGREED_HEALTH_GAIN: i64 = -2;
+const COOPCOOP_HEALTH_GAIN: i64 = 2;
+
+//the health change for each pair of moves, named after the classic dilemma terms
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoffMatrix {
+    //both cooperate
+    pub reward: i64,
+    //defect against a cooperator
+    pub temptation: i64,
+    //cooperate against a defector
+    pub sucker: i64,
+    //both defect
+    pub punishment: i64,
+}
+
+//the symmetric two player games a matrix can describe, each is a different order of the four
+//payoffs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameType {
+    //T > R > P > S, defecting always pays but mutual defection is worse than cooperating
+    #[default]
+    PrisonersDilemma,
+    //T > R > S > P, also called Hawk-Dove or Chicken, the worst is both defecting
+    #[serde(alias = "HawkDove", alias = "Chicken")]
+    Snowdrift,
+    //R > T >= P > S, cooperating is best but only if the other does too
+    StagHunt,
+    //R > T and S > P, cooperating is always best
+    Harmony,
+    //T > P > R > S, both players would rather defect
+    Deadlock,
+    //any matrix at all
+    Custom,
+}
+
+//one of the inequalities a matrix must hold to be a given game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Condition {
+    //T > R
+    TemptationOverReward,
+    //R > P
+    RewardOverPunishment,
+    //P > S
+    PunishmentOverSucker,
+    //2R > T + S
+    RewardOverAlternating,
+    //R > T
+    RewardOverTemptation,
+    //R > S
+    RewardOverSucker,
+    //S > P
+    SuckerOverPunishment,
+    //T >= P
+    TemptationNotUnderPunishment,
+    //T > P
+    TemptationOverPunishment,
+    //P > R
+    PunishmentOverReward,
+}
+
+#[derive(Debug)]
+pub enum PayoffError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Violated(GameType, Condition, PayoffMatrix),
+}
+
+impl GameType {
+    //every game, in the order the start menu cycles through them
+    pub const ALL: [GameType; 6] = [
+        GameType::PrisonersDilemma,
+        GameType::Snowdrift,
+        GameType::StagHunt,
+        GameType::Harmony,
+        GameType::Deadlock,
+        GameType::Custom,
+    ];
+
+    //the inequalities that make a matrix this game
+    pub fn conditions(&self) -> &'static [Condition] {
+        match self {
+            GameType::PrisonersDilemma => &[
+                Condition::TemptationOverReward,
+                Condition::RewardOverPunishment,
+                Condition::PunishmentOverSucker,
+                Condition::RewardOverAlternating,
+            ],
+            GameType::Snowdrift => &[
+                Condition::TemptationOverReward,
+                Condition::RewardOverSucker,
+                Condition::SuckerOverPunishment,
+            ],
+            GameType::StagHunt => &[
+                Condition::RewardOverTemptation,
+                Condition::TemptationNotUnderPunishment,
+                Condition::PunishmentOverSucker,
+            ],
+            GameType::Harmony => &[
+                Condition::RewardOverTemptation,
+                Condition::SuckerOverPunishment,
+            ],
+            GameType::Deadlock => &[
+                Condition::TemptationOverPunishment,
+                Condition::PunishmentOverReward,
+                Condition::RewardOverSucker,
+            ],
+            GameType::Custom => &[],
+        }
+    }
+
+    //the given payoffs, or the game's built in matrix when none were given, a custom game
+    //without payoffs gets the default prisoner's dilemma
+    pub fn or_preset(&self, payoffs: Option<PayoffMatrix>) -> PayoffMatrix {
+        payoffs.or_else(|| self.preset()).unwrap_or_default()
+    }
+
+    //the built in matrix of the game, on the same health scale as the prisoner's dilemma,
+    //custom games have none
+    pub fn preset(&self) -> Option<PayoffMatrix> {
+        let (reward, temptation, sucker, punishment) = match self {
+            GameType::PrisonersDilemma => return Some(PayoffMatrix::default()),
+            GameType::Snowdrift => (1, 3, -1, -3),
+            GameType::StagHunt => (2, -1, -2, -1),
+            GameType::Harmony => (2, 1, 0, -1),
+            GameType::Deadlock => (0, 3, -2, 1),
+            GameType::Custom => return None,
+        };
+        Some(PayoffMatrix {
+            reward,
+            temptation,
+            sucker,
+            punishment,
+        })
+    }
+
+    //the named game a matrix is, custom if it is none of them
+    pub fn of(matrix: &PayoffMatrix) -> GameType {
+        GameType::ALL
+            .into_iter()
+            .find(|game| *game != GameType::Custom && matrix.validate_as(*game).is_ok())
+            .unwrap_or(GameType::Custom)
+    }
+
+    //the game after this one in `ALL`, wrapping around
+    pub fn next(&self) -> GameType {
+        let i = GameType::ALL
+            .iter()
+            .position(|game| game == self)
+            .unwrap_or(0);
+        GameType::ALL[(i + 1) % GameType::ALL.len()]
+    }
+}
+
+impl Condition {
+    pub fn holds(&self, m: &PayoffMatrix) -> bool {
+        let (t, r, p, s) = (m.temptation, m.reward, m.punishment, m.sucker);
+        match self {
+            Condition::TemptationOverReward => t > r,
+            Condition::RewardOverPunishment => r > p,
+            Condition::PunishmentOverSucker => p > s,
+            Condition::RewardOverAlternating => 2 * r > t + s,
+            Condition::RewardOverTemptation => r > t,
+            Condition::RewardOverSucker => r > s,
+            Condition::SuckerOverPunishment => s > p,
+            Condition::TemptationNotUnderPunishment => t >= p,
+            Condition::TemptationOverPunishment => t > p,
+            Condition::PunishmentOverReward => p > r,
+        }
+    }
+}
+
+impl Default for PayoffMatrix {
+    fn default() -> Self {
+        Self {
+            reward: COOPCOOP_HEALTH_GAIN,
+            temptation: GREEDCOOP_HEALTH_GAIN,
+            sucker: COOPGREED_HEALTH_GAIN,
+            punishment: GREEDGREED_HEALTH_GAIN,
+        }
+    }
+}
+
+impl PayoffMatrix {
+    //reads a matrix from a ron file and checks that it is a valid dilemma
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PayoffError> {
+        Self::load_as(path, GameType::PrisonersDilemma)
+    }
+
+    //reads a matrix from a ron file and checks that it is the given game
+    pub fn load_as(path: impl AsRef<Path>, game: GameType) -> Result<Self, PayoffError> {
+        let text = fs::read_to_string(path).map_err(PayoffError::Io)?;
+        let matrix: PayoffMatrix = ron::from_str(&text).map_err(PayoffError::Parse)?;
+        matrix.validate_as(game)?;
+        Ok(matrix)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, PayoffError> {
+        let matrix: PayoffMatrix = ron::from_str(text).map_err(PayoffError::Parse)?;
+        matrix.validate()?;
+        Ok(matrix)
+    }
+
+    //checks T > R > P > S and 2R > T + S, reporting the first one that fails
+    pub fn validate(&self) -> Result<(), PayoffError> {
+        self.validate_as(GameType::PrisonersDilemma)
+    }
+
+    //checks the inequalities of the given game, reporting the first one that fails
+    pub fn validate_as(&self, game: GameType) -> Result<(), PayoffError> {
+        match game.conditions().iter().find(|c| !c.holds(self)) {
+            Some(condition) => Err(PayoffError::Violated(game, *condition, *self)),
+            None => Ok(()),
+        }
+    }
+
+    //the chance generous tit for tat forgives a defection, the largest that still beats
+    //unconditional defection (Nowak and Sigmund)
+    pub fn generosity(&self) -> f64 {
+        let (t, r, p, s) = (
+            self.temptation as f64,
+            self.reward as f64,
+            self.punishment as f64,
+            self.sucker as f64,
+        );
+        let generosity = (1.0 - (t - r) / (r - s)).min((r - p) / (t - p));
+        if generosity.is_finite() {
+            generosity.clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    //the health change for playing `own` against `other`
+    pub fn payoff(&self, own: Move, other: Move) -> i64 {
+        match (own, other) {
+            (Move::Defect, Move::Defect) => self.punishment,
+            (Move::Defect, Move::Cooperate) => self.temptation,
+            (Move::Cooperate, Move::Defect) => self.sucker,
+            (Move::Cooperate, Move::Cooperate) => self.reward,
+        }
+    }
+}
+
+impl fmt::Display for GameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            GameType::PrisonersDilemma => "Prisoner's Dilemma",
+            GameType::Snowdrift => "Snowdrift",
+            GameType::StagHunt => "Stag Hunt",
+            GameType::Harmony => "Harmony",
+            GameType::Deadlock => "Deadlock",
+            GameType::Custom => "Custom",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Condition::TemptationOverReward => "temptation > reward",
+            Condition::RewardOverPunishment => "reward > punishment",
+            Condition::PunishmentOverSucker => "punishment > sucker",
+            Condition::RewardOverAlternating => "2 * reward > temptation + sucker",
+            Condition::RewardOverTemptation => "reward > temptation",
+            Condition::RewardOverSucker => "reward > sucker",
+            Condition::SuckerOverPunishment => "sucker > punishment",
+            Condition::TemptationNotUnderPunishment => "temptation >= punishment",
+            Condition::TemptationOverPunishment => "temptation > punishment",
+            Condition::PunishmentOverReward => "punishment > reward",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for PayoffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PayoffError::Io(e) => write!(f, "could not read payoff file: {e}"),
+            PayoffError::Parse(e) => write!(f, "could not parse payoff file: {e}"),
+            PayoffError::Violated(game, condition, m) => write!(
+                f,
+                "payoffs are not a {game}, {condition} does not hold \
+                 (T = {}, R = {}, P = {}, S = {})",
+                m.temptation, m.reward, m.punishment, m.sucker
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PayoffError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(reward: i64, temptation: i64, sucker: i64, punishment: i64) -> PayoffMatrix {
+        PayoffMatrix {
+            reward,
+            temptation,
+            sucker,
+            punishment,
+        }
+    }
+
+    #[test]
+    fn default_is_a_dilemma() {
+        assert!(PayoffMatrix::default().validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_the_first_broken_condition() {
+        let cases = [
+            (matrix(3, 3, -2, -1), Condition::TemptationOverReward),
+            (matrix(-1, 3, -2, -1), Condition::RewardOverPunishment),
+            (matrix(2, 3, -1, -1), Condition::PunishmentOverSucker),
+            (matrix(2, 6, -2, -1), Condition::RewardOverAlternating),
+        ];
+        for (payoffs, broken) in cases {
+            match payoffs.validate() {
+                Err(PayoffError::Violated(GameType::PrisonersDilemma, condition, _)) => {
+                    assert_eq!(condition, broken)
+                }
+                other => panic!("{payoffs:?} gave {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn from_ron_validates() {
+        let valid = "(reward: 2, temptation: 3, sucker: -2, punishment: -1)";
+        assert_eq!(
+            PayoffMatrix::from_ron(valid).unwrap(),
+            PayoffMatrix::default()
+        );
+        let invalid = "(reward: 3, temptation: 2, sucker: -2, punishment: -1)";
+        assert!(matches!(
+            PayoffMatrix::from_ron(invalid),
+            Err(PayoffError::Violated(..))
+        ));
+        assert!(matches!(
+            PayoffMatrix::from_ron("(reward: 2)"),
+            Err(PayoffError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn presets_are_their_own_game() {
+        for game in GameType::ALL {
+            let Some(preset) = game.preset() else {
+                assert_eq!(game, GameType::Custom);
+                continue;
+            };
+            assert!(preset.validate_as(game).is_ok(), "{game}");
+            assert_eq!(GameType::of(&preset), game);
+        }
+    }
+
+    #[test]
+    fn custom_accepts_anything() {
+        let payoffs = matrix(1, 1, -1, -1);
+        assert!(payoffs.validate_as(GameType::Custom).is_ok());
+        assert!(payoffs.validate().is_err());
+        assert_eq!(GameType::of(&payoffs), GameType::Custom);
+    }
+
+    #[test]
+    fn payoff_looks_up_both_moves() {
+        let payoffs = PayoffMatrix::default();
+        assert_eq!(payoffs.payoff(Move::Cooperate, Move::Cooperate), 2);
+        assert_eq!(payoffs.payoff(Move::Defect, Move::Cooperate), 3);
+        assert_eq!(payoffs.payoff(Move::Cooperate, Move::Defect), -2);
+        assert_eq!(payoffs.payoff(Move::Defect, Move::Defect), -1);
+    }
+}
@@ -0,0 +1,278 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use rand::RngCore;
+
+use crate::{
+    agent::{Agent, AgentId},
+    encounter::Side,
+    memory::History,
+    noise::Noise,
+    payoff::PayoffMatrix,
+    public_goods::PublicGoods,
+    strategy::{Move, StrategyId, StrategyRegistry},
+};
+
+//every living agent in a run plus the rules they play by
+#[derive(Debug, Clone, Default)]
+pub struct Population {
+    pub payoffs: PayoffMatrix,
+    pub noise: Noise,
+    pub strategies: Arc<StrategyRegistry>,
+    agents: BTreeMap<AgentId, Agent>,
+    next_id: AgentId,
+}
+
+impl Population {
+    pub fn new(payoffs: PayoffMatrix, strategies: Arc<StrategyRegistry>) -> Self {
+        Self {
+            payoffs,
+            noise: Noise::default(),
+            strategies,
+            agents: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    //adds a new agent and returns its id
+    pub fn spawn(&mut self, strategy: StrategyId, healthpoints: i64) -> AgentId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.agents
+            .insert(id, Agent::new(id, strategy, healthpoints));
+        id
+    }
+
+    //rebuilds a population from saved agents, `next_id` is the id the next spawn gets
+    pub fn from_agents(
+        payoffs: PayoffMatrix,
+        strategies: Arc<StrategyRegistry>,
+        agents: Vec<Agent>,
+        next_id: AgentId,
+    ) -> Self {
+        Self {
+            payoffs,
+            noise: Noise::default(),
+            strategies,
+            agents: agents.into_iter().map(|a| (a.id, a)).collect(),
+            next_id,
+        }
+    }
+
+    pub fn next_id(&self) -> AgentId {
+        self.next_id
+    }
+
+    pub fn get(&self, id: AgentId) -> Option<&Agent> {
+        self.agents.get(&id)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: AgentId) -> Option<&mut Agent> {
+        self.agents.get_mut(&id)
+    }
+
+    //removes an agent and makes everyone else forget it
+    pub fn remove(&mut self, id: AgentId) -> Option<Agent> {
+        let removed = self.agents.remove(&id);
+        if removed.is_some() {
+            for agent in self.agents.values_mut() {
+                agent.memory.forget(id);
+            }
+        }
+        removed
+    }
+
+    pub fn agents(&self) -> impl Iterator<Item = &Agent> {
+        self.agents.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.agents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.agents.is_empty()
+    }
+
+    //how many agents following a strategy are alive
+    pub fn count(&self, strategy: StrategyId) -> usize {
+        self.agents().filter(|a| a.strategy == strategy).count()
+    }
+
+    //the summed health of every agent following a strategy
+    pub fn total_hp(&self, strategy: StrategyId) -> i64 {
+        self.agents()
+            .filter(|a| a.strategy == strategy)
+            .map(|a| a.healthpoints)
+            .sum()
+    }
+
+    //plays one symmetric game, both agents pick their move from what they remember of each
+    //other and both payoffs are applied together, dead agents are removed from the population,
+    //with noise a move can come out flipped and each side can misremember the other's
+    pub fn play(&mut self, a: AgentId, b: AgentId, rng: &mut dyn RngCore) -> Option<[Side; 2]> {
+        if a == b {
+            return None;
+        }
+        let agent_a = self.agents.get(&a)?;
+        let agent_b = self.agents.get(&b)?;
+        let move_a = self.decide(agent_a, agent_a.memory.history(b), rng)?;
+        let move_b = self.decide(agent_b, agent_b.memory.history(a), rng)?;
+        let (move_a, move_b) = (
+            self.noise.execute(move_a, rng),
+            self.noise.execute(move_b, rng),
+        );
+
+        let sides = [(a, b, move_a, move_b), (b, a, move_b, move_a)].map(
+            |(agent, opponent, own_move, opponent_move)| {
+                let payoff = self.payoffs.payoff(own_move, opponent_move);
+                let target = self
+                    .agents
+                    .get_mut(&agent)
+                    .expect("both agents were looked up above");
+                let seen = self.noise.perceive(opponent_move, rng);
+                target.memory.record(opponent, own_move, seen);
+                let actual_payoff = payoff.max(-target.healthpoints);
+                target.healthpoints += actual_payoff;
+                Side {
+                    agent,
+                    strategy: target.strategy,
+                    played: own_move,
+                    payoff: actual_payoff,
+                    healthpoints: target.healthpoints,
+                    died: !target.is_alive(),
+                }
+            },
+        );
+
+        for side in &sides {
+            if side.died {
+                self.remove(side.agent);
+            }
+        }
+        Some(sides)
+    }
+
+    //plays one public goods game between the agents given, each contributes `stake` or keeps
+    //it and the grown pot is split equally between all of them, every agent then remembers
+    //its move against what most of the others did, returns the pot and each agent's side
+    pub fn play_group(
+        &mut self,
+        members: &[AgentId],
+        public_goods: &PublicGoods,
+        rng: &mut dyn RngCore,
+    ) -> Option<(i64, Vec<Side>)> {
+        let mut played = Vec::with_capacity(members.len());
+        for id in members {
+            let Some(agent) = self.agents.get(id) else {
+                continue;
+            };
+            let picked = self.decide(agent, agent.memory.group(), rng)?;
+            played.push((*id, self.noise.execute(picked, rng)));
+        }
+        if played.len() < public_goods.min_players.max(1) {
+            return None;
+        }
+
+        let contributors = played
+            .iter()
+            .filter(|(_, own)| *own == Move::Cooperate)
+            .count();
+        let pot = public_goods.pot(contributors);
+        //the whole pot is paid out, what doesn't split evenly goes a point each to the lowest
+        //ids, members are in id order
+        let share = pot / played.len() as i64;
+        let remainder = (pot % played.len() as i64) as usize;
+        let others = played.len() - 1;
+        let mut sides = Vec::with_capacity(played.len());
+        for (rank, (id, own)) in played.into_iter().enumerate() {
+            let contributed = own == Move::Cooperate;
+            //most of the others contributed, counting ties as cooperation
+            let others_contributing = contributors - usize::from(contributed);
+            let theirs = if others_contributing * 2 >= others {
+                Move::Cooperate
+            } else {
+                Move::Defect
+            };
+            let seen = self.noise.perceive(theirs, rng);
+            let payoff = share + i64::from(rank < remainder)
+                - if contributed { public_goods.stake } else { 0 };
+            let target = self
+                .agents
+                .get_mut(&id)
+                .expect("every member was looked up above");
+            target.memory.record_group(own, seen);
+            let actual_payoff = payoff.max(-target.healthpoints);
+            target.healthpoints += actual_payoff;
+            sides.push(Side {
+                agent: id,
+                strategy: target.strategy,
+                played: own,
+                payoff: actual_payoff,
+                healthpoints: target.healthpoints,
+                died: !target.is_alive(),
+            });
+        }
+
+        for side in &sides {
+            if side.died {
+                self.remove(side.agent);
+            }
+        }
+        Some((pot, sides))
+    }
+
+    //asks an agent's genome or else its strategy for its move given what it remembers
+    fn decide(
+        &self,
+        agent: &Agent,
+        history: Option<&History>,
+        rng: &mut dyn RngCore,
+    ) -> Option<Move> {
+        if let Some(genome) = agent.genome {
+            return Some(genome.decide(history));
+        }
+        let strategy = self.strategies.get(agent.strategy)?;
+        Some(strategy.decide(history, &self.payoffs, rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    #[test]
+    fn the_whole_pot_is_paid_out() {
+        let mut population = Population::new(PayoffMatrix::default(), Arc::default());
+        let members = [
+            population.spawn(0, 10),
+            population.spawn(0, 10),
+            population.spawn(1, 10),
+        ];
+        let public_goods = PublicGoods {
+            multiplier: 2.5,
+            ..PublicGoods::default()
+        };
+        let (pot, sides) = population
+            .play_group(&members, &public_goods, &mut rng::seeded(0))
+            .unwrap();
+        //two stakes of 2 grow into 10, which doesn't split evenly in three
+        assert_eq!(pot, 10);
+        let payoffs: Vec<_> = sides.iter().map(|side| side.payoff).collect();
+        assert_eq!(payoffs, [2, 1, 3]);
+        let paid: i64 = sides
+            .iter()
+            .map(|side| side.payoff + if side.played == Move::Cooperate { 2 } else { 0 })
+            .sum();
+        assert_eq!(paid, pot);
+    }
+
+    #[test]
+    fn too_small_groups_do_not_play() {
+        let mut population = Population::new(PayoffMatrix::default(), Arc::default());
+        let members = [population.spawn(0, 10)];
+        assert!(population
+            .play_group(&members, &PublicGoods::default(), &mut rng::seeded(0))
+            .is_none());
+    }
+}
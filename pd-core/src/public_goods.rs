@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    agent::AgentId,
+    arena::{HALF_HEIGHT, HALF_WIDTH},
+    encounter::Side,
+};
+
+//a rectangle of the arena where whoever stands inside plays the public goods game
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub center: [f32; 2],
+    pub half_size: [f32; 2],
+}
+
+//the settings of the n-player mode, every `interval` ticks the agents standing in each zone
+//either put `stake` HP in a pot or keep it, the pot is multiplied by `multiplier` and split
+//equally between everyone in the zone, the points left over go one each to the lowest ids
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PublicGoods {
+    pub zones: Vec<Zone>,
+    pub stake: i64,
+    //r, the game is only a dilemma while 1 < r < the number of players
+    pub multiplier: f64,
+    pub interval: u64,
+    //the fewest agents in a zone for a game to happen
+    pub min_players: usize,
+}
+
+//how agents play eachother
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum Interaction {
+    //every pair that touches plays the two player game
+    #[default]
+    Pairwise,
+    //touching does nothing, agents only play in the zones
+    PublicGoods(PublicGoods),
+}
+
+//one public goods game, the pot is what was shared out after the multiplier, all of it is paid
+//to the members
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Gathering {
+    pub tick: u64,
+    pub zone: usize,
+    pub pot: i64,
+    //in agent order, a side's move is whether it contributed
+    pub members: Vec<Side>,
+}
+
+impl Zone {
+    pub fn contains(&self, position: [f32; 2]) -> bool {
+        (position[0] - self.center[0]).abs() <= self.half_size[0]
+            && (position[1] - self.center[1]).abs() <= self.half_size[1]
+    }
+}
+
+impl Default for PublicGoods {
+    //three zones spread across the arena, a pot multiplied by 3 and a game every two seconds
+    fn default() -> Self {
+        let half_size = [HALF_WIDTH / 6.0, HALF_HEIGHT / 2.0];
+        Self {
+            zones: [-2.0 / 3.0, 0.0, 2.0 / 3.0]
+                .map(|x| Zone {
+                    center: [x * HALF_WIDTH, 0.0],
+                    half_size,
+                })
+                .to_vec(),
+            stake: 2,
+            multiplier: 3.0,
+            interval: 120,
+            min_players: 2,
+        }
+    }
+}
+
+impl PublicGoods {
+    pub fn is_due(&self, tick: u64) -> bool {
+        self.interval > 0 && tick.is_multiple_of(self.interval)
+    }
+
+    //who is in which zone, an agent inside overlapping zones only counts for the first, zones
+    //with too few agents are left out
+    pub fn groups(&self, positions: &[(AgentId, [f32; 2])]) -> Vec<(usize, Vec<AgentId>)> {
+        let mut groups = vec![Vec::new(); self.zones.len()];
+        for (agent, position) in positions {
+            if let Some(zone) = self.zones.iter().position(|z| z.contains(*position)) {
+                groups[zone].push(*agent);
+            }
+        }
+        groups
+            .into_iter()
+            .enumerate()
+            .filter(|(_, members)| members.len() >= self.min_players.max(1))
+            .map(|(zone, mut members)| {
+                members.sort_unstable();
+                (zone, members)
+            })
+            .collect()
+    }
+
+    //the pot a number of contributions grows into, rounded down
+    pub fn pot(&self, contributors: usize) -> i64 {
+        (self.stake as f64 * contributors as f64 * self.multiplier.max(0.0)).floor() as i64
+    }
+}
+
+impl Interaction {
+    pub fn is_pairwise(&self) -> bool {
+        matches!(self, Interaction::Pairwise)
+    }
+
+    pub fn public_goods(&self) -> Option<&PublicGoods> {
+        match self {
+            Interaction::Pairwise => None,
+            Interaction::PublicGoods(public_goods) => Some(public_goods),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{payoff::PayoffMatrix, population::Population, rng};
+
+    #[test]
+    fn agents_are_grouped_by_zone() {
+        let public_goods = PublicGoods::default();
+        let zone = |i: usize| public_goods.zones[i].center;
+        let outside = [0.0, HALF_HEIGHT * 0.9];
+        let positions = [
+            (5, zone(0)),
+            (3, zone(2)),
+            (2, [zone(0)[0] + HALF_WIDTH / 10.0, 1.0]),
+            (7, zone(1)),
+            (4, zone(2)),
+            (8, outside),
+        ];
+        //the middle zone has too few agents and the one outside is in no zone
+        assert_eq!(
+            public_goods.groups(&positions),
+            [(0, vec![2, 5]), (2, vec![3, 4])]
+        );
+        let everyone = PublicGoods {
+            min_players: 0,
+            ..public_goods.clone()
+        };
+        //an empty zone is still left out
+        assert_eq!(
+            everyone.groups(&positions[..4]),
+            [(0, vec![2, 5]), (1, vec![7]), (2, vec![3])]
+        );
+        assert!(everyone.groups(&[(8, outside)]).is_empty());
+
+        //overlapping zones, the first one wins
+        let overlapping = PublicGoods {
+            zones: vec![public_goods.zones[1], public_goods.zones[1]],
+            min_players: 1,
+            ..public_goods
+        };
+        assert_eq!(overlapping.groups(&[(1, [0.0, 0.0])]), [(0, vec![1])]);
+    }
+
+    #[test]
+    fn pots_grow_by_the_multiplier() {
+        let public_goods = PublicGoods::default();
+        assert_eq!(public_goods.pot(0), 0);
+        assert_eq!(public_goods.pot(1), 6);
+        assert_eq!(public_goods.pot(4), 24);
+        let low = PublicGoods {
+            multiplier: 1.25,
+            ..public_goods.clone()
+        };
+        assert_eq!(low.pot(1), 2);
+        let negative = PublicGoods {
+            multiplier: -1.0,
+            ..public_goods
+        };
+        assert_eq!(negative.pot(3), 0);
+    }
+
+    //the payoff of every member of a group of Coop and Greed agents, in that order
+    fn payoffs(cooperators: usize, defectors: usize) -> (i64, Vec<i64>) {
+        let mut population = Population::new(PayoffMatrix::default(), Arc::default());
+        let members: Vec<_> = (0..cooperators)
+            .map(|_| 0)
+            .chain((0..defectors).map(|_| 1))
+            .map(|strategy| population.spawn(strategy, 10))
+            .collect();
+        let (pot, sides) = population
+            .play_group(&members, &PublicGoods::default(), &mut rng::seeded(0))
+            .unwrap();
+        (pot, sides.iter().map(|side| side.payoff).collect())
+    }
+
+    #[test]
+    fn the_pot_is_shared_by_everyone() {
+        //one stake of 2 grows into 6, the contributor only gets its stake back
+        assert_eq!(payoffs(1, 2), (6, vec![0, 2, 2]));
+        assert_eq!(payoffs(2, 2), (12, vec![1, 1, 3, 3]));
+        assert_eq!(payoffs(0, 3), (0, vec![0, 0, 0]));
+        assert_eq!(payoffs(3, 0), (18, vec![4, 4, 4]));
+    }
+
+    #[test]
+    fn games_happen_every_interval() {
+        let public_goods = PublicGoods::default();
+        assert!(public_goods.is_due(0));
+        assert!(!public_goods.is_due(119));
+        assert!(public_goods.is_due(120));
+        assert!(public_goods.is_due(240));
+        let never = PublicGoods {
+            interval: 0,
+            ..public_goods
+        };
+        assert!(!never.is_due(0) && !never.is_due(120));
+    }
+}
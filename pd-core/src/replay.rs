@@ -0,0 +1,414 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    agent::AgentId, encounter::Encounter, lineage::LineageNode, payoff::PayoffMatrix,
+    public_goods::Gathering, simulation::Simulation, strategy::StrategyId,
+};
+
+//ticks between recorded positions, positions in between are interpolated on playback
+pub const FRAME_INTERVAL: u64 = 6;
+
+//an agent as it entered the run, or as it was when recording started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayAgent {
+    pub id: AgentId,
+    pub strategy: StrategyId,
+    pub healthpoints: i64,
+    pub born: u64,
+    //set for offspring, whose health came out of the parent's when they were born after the
+    //first tick
+    #[serde(default)]
+    pub parent: Option<AgentId>,
+    #[serde(default)]
+    pub generation: u32,
+}
+
+//where every living agent was at a tick
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    pub tick: u64,
+    //sorted by agent
+    pub positions: Vec<(AgentId, [f32; 2])>,
+}
+
+//a recorded run, the starting state plus every encounter, enough to rebuild the arena at any
+//tick without simulating it again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub payoffs: PayoffMatrix,
+    pub strategies: Vec<String>,
+    //every agent that took part, in the order they appeared, the ones alive when recording
+    //started are born on the first tick
+    pub agents: Vec<ReplayAgent>,
+    pub encounters: Vec<Encounter>,
+    #[serde(default)]
+    pub gatherings: Vec<Gathering>,
+    pub frames: Vec<Frame>,
+    pub first_tick: u64,
+    pub last_tick: u64,
+}
+
+impl Replay {
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut file, self)?;
+        file.flush()
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    //where an agent was at a tick, between two frames the position is interpolated
+    pub fn position(&self, agent: AgentId, tick: u64) -> Option<[f32; 2]> {
+        let next = self.frames.partition_point(|f| f.tick < tick);
+        let find = |frame: &Frame| {
+            frame
+                .positions
+                .binary_search_by_key(&agent, |(id, _)| *id)
+                .ok()
+                .map(|i| frame.positions[i].1)
+        };
+        let after = self.frames.get(next);
+        let before = next.checked_sub(1).and_then(|i| self.frames.get(i));
+        match (before.and_then(|f| find(f).map(|p| (f.tick, p))), after) {
+            (Some((from_tick, from)), Some(frame)) => match find(frame) {
+                Some(to) => {
+                    let t = (tick - from_tick) as f32 / (frame.tick - from_tick).max(1) as f32;
+                    Some([
+                        from[0] + (to[0] - from[0]) * t,
+                        from[1] + (to[1] - from[1]) * t,
+                    ])
+                }
+                None => Some(from),
+            },
+            (Some((_, from)), None) => Some(from),
+            (None, Some(frame)) => find(frame),
+            (None, None) => None,
+        }
+    }
+}
+
+//builds a replay while a run goes on
+#[derive(Debug, Clone)]
+pub struct ReplayRecorder {
+    replay: Replay,
+    //agents with an id below this are already recorded, ids only ever go up
+    next_agent: AgentId,
+}
+
+impl Default for ReplayRecorder {
+    fn default() -> Self {
+        Self::new(&Simulation::default())
+    }
+}
+
+impl ReplayRecorder {
+    //starts recording at the simulation's current tick, the agents alive right now are the
+    //starting state rather than births
+    pub fn new(simulation: &Simulation) -> Self {
+        let tick = simulation.clock.tick();
+        let agents = simulation
+            .population
+            .agents()
+            .map(|agent| ReplayAgent {
+                id: agent.id,
+                strategy: agent.strategy,
+                healthpoints: agent.healthpoints,
+                born: tick,
+                parent: agent.parent,
+                generation: agent.generation,
+            })
+            .collect();
+        Self {
+            replay: Replay {
+                seed: simulation.seed,
+                payoffs: simulation.population.payoffs,
+                strategies: simulation
+                    .population
+                    .strategies
+                    .iter()
+                    .map(|(_, s)| s.name().to_owned())
+                    .collect(),
+                agents,
+                encounters: Vec::new(),
+                gatherings: Vec::new(),
+                frames: Vec::new(),
+                first_tick: tick,
+                last_tick: tick,
+            },
+            next_agent: simulation.population.next_id(),
+        }
+    }
+
+    //adds the agents that appeared and the encounters and public goods games played this
+    //tick, positions are kept every FRAME_INTERVAL ticks
+    pub fn record_tick(
+        &mut self,
+        simulation: &Simulation,
+        encounters: &[Encounter],
+        gatherings: &[Gathering],
+        positions: &[(AgentId, [f32; 2])],
+    ) {
+        let tick = simulation.clock.tick();
+        let known = self.next_agent;
+        for agent in simulation.population.agents().filter(|a| a.id >= known) {
+            self.replay.agents.push(ReplayAgent {
+                id: agent.id,
+                strategy: agent.strategy,
+                healthpoints: agent.healthpoints,
+                born: tick,
+                parent: agent.parent,
+                generation: agent.generation,
+            });
+            self.next_agent = agent.id + 1;
+        }
+        self.replay.encounters.extend_from_slice(encounters);
+        self.replay.gatherings.extend_from_slice(gatherings);
+
+        if (tick - self.replay.first_tick).is_multiple_of(FRAME_INTERVAL) {
+            let mut positions = positions.to_vec();
+            positions.sort_unstable_by_key(|(agent, _)| *agent);
+            self.replay.frames.push(Frame { tick, positions });
+        }
+        self.replay.last_tick = tick;
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+}
+
+//steps through a replay, forwards by applying what happened and backwards by starting over
+#[derive(Debug, Clone)]
+pub struct ReplayPlayer {
+    replay: Replay,
+    tick: u64,
+    //the living agents and their health at the current tick
+    alive: BTreeMap<AgentId, (StrategyId, i64)>,
+    next_agent: usize,
+    next_encounter: usize,
+    next_gathering: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        let mut player = Self {
+            tick: replay.first_tick,
+            replay,
+            alive: BTreeMap::new(),
+            next_agent: 0,
+            next_encounter: 0,
+            next_gathering: 0,
+        };
+        player.seek(player.tick);
+        player
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    fn rewind(&mut self) {
+        self.alive.clear();
+        self.next_agent = 0;
+        self.next_encounter = 0;
+        self.next_gathering = 0;
+        self.tick = self.replay.first_tick;
+    }
+
+    //moves to a tick, clamped to the recorded ticks
+    pub fn seek(&mut self, tick: u64) {
+        let tick = tick.clamp(self.replay.first_tick, self.replay.last_tick);
+        if tick < self.tick {
+            self.rewind();
+        }
+        //births, encounters and public goods games in the order they happened, within a tick
+        //agents play pairs first, then the zones, and split last
+        loop {
+            let next = [
+                self.replay
+                    .encounters
+                    .get(self.next_encounter)
+                    .map(|e| e.tick),
+                self.replay
+                    .gatherings
+                    .get(self.next_gathering)
+                    .map(|g| g.tick),
+                self.replay.agents.get(self.next_agent).map(|a| a.born),
+            ];
+            //the earliest, the first of them on a tie
+            let Some((kind, _)) = next
+                .iter()
+                .enumerate()
+                .filter_map(|(kind, at)| at.filter(|at| *at <= tick).map(|at| (kind, at)))
+                .min_by_key(|(_, at)| *at)
+            else {
+                break;
+            };
+            match kind {
+                0 => self.apply_encounter(self.replay.encounters[self.next_encounter]),
+                1 => self.apply_gathering(self.next_gathering),
+                _ => self.apply_birth(self.replay.agents[self.next_agent]),
+            }
+        }
+        self.tick = tick;
+    }
+
+    fn apply_birth(&mut self, agent: ReplayAgent) {
+        self.alive
+            .insert(agent.id, (agent.strategy, agent.healthpoints));
+        //agents of the first tick were already alive with their own health, their parent's
+        //health was recorded after the split
+        let parent = agent.parent.filter(|_| agent.born > self.replay.first_tick);
+        if let Some((_, healthpoints)) = parent.and_then(|p| self.alive.get_mut(&p)) {
+            *healthpoints -= agent.healthpoints;
+        }
+        self.next_agent += 1;
+    }
+
+    fn apply_encounter(&mut self, encounter: Encounter) {
+        for side in encounter.sides() {
+            if side.died {
+                self.alive.remove(&side.agent);
+            } else if let Some((_, healthpoints)) = self.alive.get_mut(&side.agent) {
+                *healthpoints = side.healthpoints;
+            }
+        }
+        self.next_encounter += 1;
+    }
+
+    fn apply_gathering(&mut self, index: usize) {
+        for side in &self.replay.gatherings[index].members {
+            if side.died {
+                self.alive.remove(&side.agent);
+            } else if let Some((_, healthpoints)) = self.alive.get_mut(&side.agent) {
+                *healthpoints = side.healthpoints;
+            }
+        }
+        self.next_gathering += 1;
+    }
+
+    //the living agents at the current tick with their strategy and health
+    pub fn alive(&self) -> impl Iterator<Item = (AgentId, StrategyId, i64)> + '_ {
+        self.alive
+            .iter()
+            .map(|(id, (strategy, healthpoints))| (*id, *strategy, *healthpoints))
+    }
+
+    pub fn position(&self, agent: AgentId) -> Option<[f32; 2]> {
+        self.replay.position(agent, self.tick)
+    }
+
+    //where an agent comes from, agents are recorded in id order
+    pub fn lineage(&self, agent: AgentId) -> Option<LineageNode> {
+        let i = self
+            .replay
+            .agents
+            .binary_search_by_key(&agent, |a| a.id)
+            .ok()?;
+        let agent = &self.replay.agents[i];
+        Some(LineageNode {
+            id: agent.id,
+            parent: agent.parent,
+            strategy: agent.strategy,
+            generation: agent.generation,
+            born: agent.born,
+            died: None,
+            genome: None,
+        })
+    }
+
+    //the summed health of a strategy's living agents at the current tick
+    pub fn total_hp(&self, strategy: StrategyId) -> i64 {
+        self.alive
+            .values()
+            .filter(|(s, _)| *s == strategy)
+            .map(|(_, healthpoints)| healthpoints)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        headless::{tests::scenario, HeadlessRun},
+        strategy::StrategyRegistry,
+    };
+
+    fn alive(simulation: &Simulation) -> Vec<(AgentId, StrategyId, i64)> {
+        simulation
+            .population
+            .agents()
+            .map(|a| (a.id, a.strategy, a.healthpoints))
+            .collect()
+    }
+
+    //plays a replay to its last tick and checks it ends where the run did, then rewinds it
+    fn check_playback(run: &HeadlessRun, at_start: &[(AgentId, StrategyId, i64)]) {
+        let replay = run.recorder.as_ref().unwrap().replay().clone();
+        let mut player = ReplayPlayer::new(replay);
+        assert_eq!(player.alive().collect::<Vec<_>>(), at_start);
+        player.seek(u64::MAX);
+        assert_eq!(player.tick(), run.simulation.clock.tick());
+        assert_eq!(player.alive().collect::<Vec<_>>(), alive(&run.simulation));
+        player.seek(0);
+        assert_eq!(player.alive().collect::<Vec<_>>(), at_start);
+    }
+
+    #[test]
+    fn replays_a_run_from_the_start() {
+        let mut run = HeadlessRun::new(&scenario(), 9, Arc::default()).unwrap();
+        let at_start = alive(&run.simulation);
+        run.record_replay();
+        run.run(600);
+        check_playback(&run, &at_start);
+    }
+
+    #[test]
+    fn replays_a_run_recorded_after_loading_a_snapshot() {
+        let strategies = Arc::new(StrategyRegistry::default());
+        let mut original = HeadlessRun::new(&scenario(), 9, strategies.clone()).unwrap();
+        original.run(600);
+        let state = original.simulation.save_state();
+        //offspring alive when recording starts must not take health from their parents again
+        assert!(state.agents.iter().any(|a| a.parent.is_some()));
+
+        let mut run = HeadlessRun::new(&scenario(), 9, strategies.clone()).unwrap();
+        run.simulation = Simulation::from_state(state, strategies).unwrap();
+        run.arena = original.arena.clone();
+        let at_start = alive(&run.simulation);
+        run.record_replay();
+        run.run(600);
+        check_playback(&run, &at_start);
+    }
+
+    #[test]
+    fn survives_a_save_and_load() {
+        let mut run = HeadlessRun::new(&scenario(), 2, Arc::default()).unwrap();
+        run.record_replay();
+        run.run(120);
+        let replay = run.recorder.unwrap().replay().clone();
+        let path = std::env::temp_dir().join(format!("pd_core_replay_{}.json", std::process::id()));
+        replay.save(&path).unwrap();
+        let loaded = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), replay);
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    agent::AgentId, events::Event, genome::Genome, simulation::Simulation, strategy::StrategyId,
+};
+
+//the most agents splitting grows a run to unless told otherwise, past a few hundred the
+//arena is packed solid and every tick is mostly encounters
+pub const DEFAULT_CAPACITY: usize = 200;
+
+//the lowest split threshold, below it a split can hand out halves with no health left
+pub const MIN_THRESHOLD: i64 = 2;
+
+//when agents split in two
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reproduction {
+    //an agent with more health than this splits into two, left out agents never split
+    pub threshold: Option<i64>,
+    //no agent splits while this many are alive, left out there is no limit
+    pub capacity: Option<usize>,
+    //the chance an offspring follows another strategy than its parent, picked evenly from
+    //the rest of the registry
+    pub mutation: f64,
+    //the chance an offspring's genome is crossed with the genome of another living agent of
+    //the same memory, only agents with a genome strategy have one
+    pub crossover: f64,
+    //the chance each gene of an offspring's genome flips
+    pub point_mutation: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReproductionError {
+    //a split threshold under MIN_THRESHOLD
+    Threshold(i64),
+}
+
+impl Reproduction {
+    //checks agents can't split into offspring without health
+    pub fn validate(&self) -> Result<(), ReproductionError> {
+        match self.threshold {
+            Some(threshold) if threshold < MIN_THRESHOLD => {
+                Err(ReproductionError::Threshold(threshold))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for ReproductionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReproductionError::Threshold(threshold) => write!(
+                f,
+                "the split threshold has to be at least {MIN_THRESHOLD}, not {threshold}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReproductionError {}
+
+impl Default for Reproduction {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            capacity: Some(DEFAULT_CAPACITY),
+            mutation: 0.0,
+            crossover: 0.0,
+            point_mutation: 0.0,
+        }
+    }
+}
+
+//an agent about to be added, either a founder or the offspring of a split
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Birth {
+    pub strategy: StrategyId,
+    pub healthpoints: i64,
+    pub parent: Option<AgentId>,
+    //left out the agent gets its strategy's genome, if it has one
+    pub genome: Option<Genome>,
+}
+
+impl Birth {
+    pub fn founder(strategy: StrategyId, healthpoints: i64) -> Self {
+        Self {
+            strategy,
+            healthpoints,
+            parent: None,
+            genome: None,
+        }
+    }
+}
+
+impl Simulation {
+    //every agent over the threshold in id order, as many as still fit under the capacity
+    pub fn due_to_split(&self, reproduction: &Reproduction) -> Vec<AgentId> {
+        let Some(threshold) = reproduction.threshold else {
+            return Vec::new();
+        };
+        let room = reproduction.capacity.map_or(usize::MAX, |capacity| {
+            capacity.saturating_sub(self.population.len())
+        });
+        self.population
+            .agents()
+            .filter(|a| a.healthpoints > threshold)
+            .map(|a| a.id)
+            .take(room)
+            .collect()
+    }
+
+    //halves a parent's health and returns the birth of its offspring, which gets the other half,
+    //the parent keeps the odd point
+    pub fn split(&mut self, parent: AgentId, reproduction: &Reproduction) -> Option<Birth> {
+        let agent = self.population.get_mut(parent)?;
+        let healthpoints = agent.healthpoints / 2;
+        agent.healthpoints -= healthpoints;
+        let (strategy, genome) = (agent.strategy, agent.genome);
+        let child_strategy = self.mutate(strategy, reproduction.mutation);
+        //an offspring that switched strategy takes the new strategy's genome as it is
+        let genome = genome
+            .filter(|_| child_strategy == strategy)
+            .map(|genome| self.recombine(parent, genome, reproduction));
+        Some(Birth {
+            strategy: child_strategy,
+            healthpoints,
+            parent: Some(parent),
+            genome,
+        })
+    }
+
+    //crosses a parent's genome with a random mate's and mutates the result, again nothing is
+    //drawn when both are off
+    fn recombine(
+        &mut self,
+        parent: AgentId,
+        genome: Genome,
+        reproduction: &Reproduction,
+    ) -> Genome {
+        let mut genome = genome;
+        if reproduction.crossover > 0.0 && self.rng.random_bool(reproduction.crossover.min(1.0)) {
+            let mates: Vec<_> = self
+                .population
+                .agents()
+                .filter(|a| a.id != parent)
+                .filter_map(|a| a.genome)
+                .filter(|mate| mate.memory() == genome.memory())
+                .collect();
+            if !mates.is_empty() {
+                let mate = mates[self.rng.random_range(0..mates.len())];
+                genome = genome.crossover(&mate, &mut self.rng);
+            }
+        }
+        genome.mutate(reproduction.point_mutation, &mut self.rng)
+    }
+
+    //the strategy an offspring of a `strategy` parent follows, nothing is drawn from the rng
+    //when mutation is off so runs without it play out the same as before
+    fn mutate(&mut self, strategy: StrategyId, chance: f64) -> StrategyId {
+        let others = self.population.strategies.len().saturating_sub(1);
+        if chance <= 0.0 || others == 0 || !self.rng.random_bool(chance.min(1.0)) {
+            return strategy;
+        }
+        let picked = self.rng.random_range(0..others);
+        if picked >= strategy {
+            picked + 1
+        } else {
+            picked
+        }
+    }
+
+    //adds a founder or an offspring, counting it as a birth and adding it to the lineage
+    pub fn birth(&mut self, birth: Birth) -> AgentId {
+        self.stats.record_birth(birth.strategy);
+        let generation = birth
+            .parent
+            .and_then(|parent| self.population.get(parent))
+            .map_or(0, |parent| parent.generation + 1);
+        let genome = birth.genome.or_else(|| {
+            self.population
+                .strategies
+                .get(birth.strategy)
+                .and_then(|s| s.genome())
+        });
+        let id = self.population.spawn(birth.strategy, birth.healthpoints);
+        if let Some(agent) = self.population.get_mut(id) {
+            agent.genome = genome;
+            agent.parent = birth.parent;
+            agent.generation = generation;
+            agent.born = self.clock.tick();
+            self.lineage.record_birth(agent);
+        }
+        if let Some(parent) = birth.parent {
+            if let (Some(agent), Some(parent)) =
+                (self.population.get(id), self.population.get(parent))
+            {
+                let event = Event::birth(
+                    self.clock.tick(),
+                    agent,
+                    parent,
+                    &self.population.strategies,
+                );
+                self.events.emit(event);
+            }
+        }
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::{Scenario, ScenarioError};
+
+    #[test]
+    fn thresholds_under_two_are_refused() {
+        let with = |threshold| Reproduction {
+            threshold,
+            ..Reproduction::default()
+        };
+        assert!(with(None).validate().is_ok());
+        assert!(with(Some(MIN_THRESHOLD)).validate().is_ok());
+        assert_eq!(
+            with(Some(1)).validate(),
+            Err(ReproductionError::Threshold(1))
+        );
+        assert!(matches!(
+            Scenario::from_ron("(reproduction: (threshold: Some(0)))"),
+            Err(ScenarioError::Reproduction(ReproductionError::Threshold(0)))
+        ));
+    }
+
+    #[test]
+    fn splits_halve_the_parent() {
+        let mut simulation = Simulation::default();
+        let parent = simulation.spawn(0, 21);
+        let reproduction = Reproduction {
+            threshold: Some(20),
+            ..Reproduction::default()
+        };
+        assert_eq!(simulation.due_to_split(&reproduction), [parent]);
+        let birth = simulation.split(parent, &reproduction).unwrap();
+        assert_eq!(birth.healthpoints, 10);
+        assert_eq!(birth.parent, Some(parent));
+        assert_eq!(simulation.population.get(parent).unwrap().healthpoints, 11);
+        let child = simulation.birth(birth);
+        assert_eq!(simulation.population.get(child).unwrap().generation, 1);
+        assert!(simulation.due_to_split(&reproduction).is_empty());
+    }
+}
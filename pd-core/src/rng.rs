@@ -0,0 +1,14 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+//the random source of a run, every random choice in a run is drawn from one of these
+pub type SimRng = ChaCha8Rng;
+
+pub fn seeded(seed: u64) -> SimRng {
+    SimRng::seed_from_u64(seed)
+}
+
+//a fresh seed for runs that weren't given one, kept short so it is easy to type back in
+pub fn random_seed() -> u64 {
+    rand::random::<u32>() as u64
+}
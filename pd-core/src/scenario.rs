@@ -0,0 +1,174 @@
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    arena::BASE_HEALTH,
+    genome::GenomeError,
+    noise::Noise,
+    outcome::EndConditions,
+    payoff::{GameType, PayoffError, PayoffMatrix},
+    public_goods::Interaction,
+    reproduction::{Reproduction, ReproductionError},
+    stats::DEFAULT_SAMPLE_INTERVAL,
+    strategy::{StrategyId, StrategyRegistry},
+};
+
+//the settings of a run without a window, read from a ron file, anything left out keeps its
+//default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    //how many agents of each strategy to start with, by strategy name
+    pub spawn: BTreeMap<String, i64>,
+    //the health every agent starts with
+    pub healthpoints: i64,
+    //the game the payoffs have to be, a prisoner's dilemma unless set
+    pub game: GameType,
+    //left out, the game's built in matrix is used
+    pub payoffs: Option<PayoffMatrix>,
+    pub end_conditions: EndConditions,
+    pub reproduction: Reproduction,
+    //pairwise games on contact, or public goods games in zones of the arena
+    pub interaction: Interaction,
+    //the chances a move is flipped or misremembered, both 0 unless set
+    pub noise: Noise,
+    //ticks between time series samples
+    pub sample_interval: u64,
+    //used when no seed is given on the command line
+    pub seed: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+    Payoffs(PayoffError),
+    //a strategy name that isn't in the registry
+    UnknownStrategy(String),
+    Genome(GenomeError),
+    Reproduction(ReproductionError),
+    //a mixed strategy cooperating with a chance outside 0 to 1
+    Cooperation(String),
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            spawn: BTreeMap::new(),
+            healthpoints: BASE_HEALTH,
+            game: GameType::default(),
+            payoffs: None,
+            end_conditions: EndConditions::default(),
+            reproduction: Reproduction::default(),
+            interaction: Interaction::default(),
+            noise: Noise::default(),
+            sample_interval: DEFAULT_SAMPLE_INTERVAL,
+            seed: None,
+        }
+    }
+}
+
+impl Scenario {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let text = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        Self::from_ron(&text)
+    }
+
+    //parses a scenario and checks it
+    pub fn from_ron(text: &str) -> Result<Self, ScenarioError> {
+        let scenario: Scenario = parse(text)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    //the payoffs every encounter is played with
+    pub fn payoffs(&self) -> PayoffMatrix {
+        self.game.or_preset(self.payoffs)
+    }
+
+    //checks the payoffs are a valid game of the scenario's type and agents split sensibly
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        self.payoffs()
+            .validate_as(self.game)
+            .map_err(ScenarioError::Payoffs)?;
+        self.reproduction
+            .validate()
+            .map_err(ScenarioError::Reproduction)
+    }
+
+    //the spawn counts by strategy id, in registry order
+    pub fn spawn_counts(
+        &self,
+        registry: &StrategyRegistry,
+    ) -> Result<Vec<(StrategyId, i64)>, ScenarioError> {
+        if let Some(name) = self.spawn.keys().find(|name| registry.find(name).is_none()) {
+            return Err(ScenarioError::UnknownStrategy(name.clone()));
+        }
+        Ok(registry
+            .iter()
+            .filter_map(|(id, strategy)| {
+                self.spawn
+                    .get(strategy.name())
+                    .map(|count| (id, (*count).max(0)))
+            })
+            .collect())
+    }
+}
+
+//parses a ron file of settings, optional fields can be written without `Some(...)`
+pub(crate) fn parse<T: DeserializeOwned>(text: &str) -> Result<T, ScenarioError> {
+    ron::Options::default()
+        .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+        .from_str(text)
+        .map_err(ScenarioError::Parse)
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "could not read scenario file: {e}"),
+            ScenarioError::Parse(e) => write!(f, "could not parse scenario file: {e}"),
+            ScenarioError::Payoffs(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::UnknownStrategy(name) => {
+                write!(f, "invalid scenario: there is no strategy called {name}")
+            }
+            ScenarioError::Genome(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::Reproduction(e) => write!(f, "invalid scenario: {e}"),
+            ScenarioError::Cooperation(name) => {
+                write!(f, "{name} has to cooperate with a chance from 0 to 1")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn left_out_payoffs_are_the_games_preset() {
+        let scenario =
+            Scenario::from_ron(r#"(spawn: {"Coop": 5, "Greed": 5}, game: Snowdrift)"#).unwrap();
+        assert_eq!(scenario.payoffs, None);
+        assert_eq!(Some(scenario.payoffs()), GameType::Snowdrift.preset());
+        assert_eq!(Scenario::default().payoffs(), PayoffMatrix::default());
+    }
+
+    #[test]
+    fn written_payoffs_need_no_some() {
+        let scenario = Scenario::from_ron(
+            "(payoffs: (reward: 3, temptation: 5, sucker: -3, punishment: -1), seed: 4)",
+        )
+        .unwrap();
+        assert_eq!(scenario.payoffs().temptation, 5);
+        assert_eq!(scenario.seed, Some(4));
+        //a preset of another game is still refused
+        assert!(matches!(
+            Scenario::from_ron("(game: Snowdrift, payoffs: (reward: 2, temptation: 3, sucker: -2, punishment: -1))"),
+            Err(ScenarioError::Payoffs(_))
+        ));
+    }
+}
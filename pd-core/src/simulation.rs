@@ -0,0 +1,223 @@
+use std::sync::Arc;
+
+use crate::{
+    agent::AgentId,
+    clock::SimClock,
+    encounter::{Cooldowns, Encounter},
+    events::{Event, EventBus},
+    lineage::Lineage,
+    payoff::PayoffMatrix,
+    population::Population,
+    public_goods::{Gathering, PublicGoods},
+    reproduction::Birth,
+    rng::{self, SimRng},
+    stats::TimeSeries,
+    strategy::{StrategyId, StrategyRegistry},
+};
+
+//a single run, the population plus the seeded random source all of its choices come from
+#[derive(Debug)]
+pub struct Simulation {
+    pub seed: u64,
+    pub rng: SimRng,
+    pub clock: SimClock,
+    pub population: Population,
+    pub events: EventBus,
+    pub stats: TimeSeries,
+    pub lineage: Lineage,
+    pub(crate) cooldowns: Cooldowns,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new(0, PayoffMatrix::default(), Arc::default())
+    }
+}
+
+impl Simulation {
+    pub fn new(seed: u64, payoffs: PayoffMatrix, strategies: Arc<StrategyRegistry>) -> Self {
+        let mut events = EventBus::default();
+        events.emit(Event::run(seed, &payoffs, &strategies));
+        Self {
+            seed,
+            rng: rng::seeded(seed),
+            clock: SimClock::default(),
+            population: Population::new(payoffs, strategies),
+            events,
+            stats: TimeSeries::default(),
+            lineage: Lineage::default(),
+            cooldowns: Cooldowns::default(),
+        }
+    }
+
+    //adds a new founder, counting it as a birth
+    pub fn spawn(&mut self, strategy: StrategyId, healthpoints: i64) -> AgentId {
+        self.birth(Birth::founder(strategy, healthpoints))
+    }
+
+    //wraps up the current tick, tracking the population and sampling the time series when
+    //one is due
+    pub fn finish_tick(&mut self) {
+        let tick = self.clock.tick();
+        self.stats.track(tick, &self.population);
+        self.stats.sample_if_due(tick, &self.population);
+    }
+
+    //samples the current tick unless it was just sampled, so a finished run's time series
+    //ends on its final state
+    pub fn final_sample(&mut self) {
+        let tick = self.clock.tick();
+        if self.stats.samples().last().is_none_or(|s| s.tick != tick) {
+            self.stats.sample(tick, &self.population);
+        }
+    }
+
+    //plays every touching pair found this tick, once per pair and in a fixed order,
+    //pairs still on cooldown from an earlier encounter are skipped
+    pub fn resolve(&mut self, touching: &[(AgentId, AgentId)]) -> Vec<Encounter> {
+        let tick = self.clock.tick();
+        let mut pairs: Vec<_> = touching
+            .iter()
+            .map(|(a, b)| Cooldowns::key(*a, *b))
+            .filter(|(a, b)| a != b)
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+
+        let mut encounters = Vec::new();
+        for (a, b) in pairs {
+            if !self.cooldowns.ready(a, b, tick) {
+                continue;
+            }
+            let Some([side_a, side_b]) = self.population.play(a, b, &mut self.rng) else {
+                //one of them died earlier this tick
+                continue;
+            };
+            self.cooldowns.start(a, b, tick);
+            let encounter = Encounter {
+                tick,
+                a: side_a,
+                b: side_b,
+            };
+            self.events
+                .emit(Event::encounter(&encounter, &self.population.strategies));
+            self.stats.record_encounter(&encounter);
+            for side in encounter.sides().into_iter().filter(|side| side.died) {
+                self.lineage.record_death(side.agent, tick);
+            }
+            encounters.push(encounter);
+        }
+        self.cooldowns.prune(tick);
+        encounters
+    }
+
+    //plays a public goods game in every zone with enough agents in it when one is due, zones
+    //are played in order
+    pub fn gather(
+        &mut self,
+        public_goods: &PublicGoods,
+        positions: &[(AgentId, [f32; 2])],
+    ) -> Vec<Gathering> {
+        let tick = self.clock.tick();
+        if !public_goods.is_due(tick) {
+            return Vec::new();
+        }
+        let mut gatherings = Vec::new();
+        for (zone, members) in public_goods.groups(positions) {
+            let Some((pot, members)) =
+                self.population
+                    .play_group(&members, public_goods, &mut self.rng)
+            else {
+                continue;
+            };
+            let gathering = Gathering {
+                tick,
+                zone,
+                pot,
+                members,
+            };
+            self.events
+                .emit(Event::gathering(&gathering, &self.population.strategies));
+            self.stats.record_gathering(&gathering);
+            for side in gathering.members.iter().filter(|side| side.died) {
+                self.lineage.record_death(side.agent, tick);
+            }
+            gatherings.push(gathering);
+        }
+        gatherings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encounter::ENCOUNTER_COOLDOWN, strategy::Move};
+
+    //a simulation with one Coop and one Greed agent
+    fn coop_and_greed(healthpoints: i64) -> (Simulation, AgentId, AgentId) {
+        let mut simulation = Simulation::default();
+        let registry = simulation.population.strategies.clone();
+        let coop = simulation.spawn(registry.find("Coop").unwrap(), healthpoints);
+        let greed = simulation.spawn(registry.find("Greed").unwrap(), healthpoints);
+        (simulation, coop, greed)
+    }
+
+    #[test]
+    fn plays_each_pair_once_per_tick() {
+        let (mut simulation, coop, greed) = coop_and_greed(10);
+        simulation.clock.advance();
+        let encounters = simulation.resolve(&[(coop, greed), (greed, coop), (coop, coop)]);
+        assert_eq!(encounters.len(), 1);
+        let encounter = encounters[0];
+        assert_eq!(encounter.tick, 1);
+        assert_eq!((encounter.a.agent, encounter.b.agent), (coop, greed));
+        assert_eq!(encounter.a.played, Move::Cooperate);
+        assert_eq!(encounter.b.played, Move::Defect);
+        assert_eq!((encounter.a.payoff, encounter.b.payoff), (-2, 3));
+        assert_eq!(simulation.population.get(coop).unwrap().healthpoints, 8);
+        assert_eq!(simulation.population.get(greed).unwrap().healthpoints, 13);
+        assert!(matches!(
+            simulation.events.events().last(),
+            Some(Event::Encounter { tick: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn pairs_wait_out_the_cooldown() {
+        let (mut simulation, coop, greed) = coop_and_greed(100);
+        simulation.clock.advance();
+        assert_eq!(simulation.resolve(&[(coop, greed)]).len(), 1);
+        for _ in 1..ENCOUNTER_COOLDOWN {
+            simulation.clock.advance();
+            assert!(simulation.resolve(&[(coop, greed)]).is_empty());
+        }
+        simulation.clock.advance();
+        assert_eq!(simulation.resolve(&[(greed, coop)]).len(), 1);
+    }
+
+    #[test]
+    fn the_dead_are_removed_and_skipped() {
+        let (mut simulation, coop, greed) = coop_and_greed(2);
+        let other = simulation.spawn(simulation.population.get(coop).unwrap().strategy, 2);
+        simulation.clock.advance();
+        let encounters = simulation.resolve(&[(coop, greed), (coop, other)]);
+        assert_eq!(encounters.len(), 1);
+        assert!(encounters[0].a.died);
+        assert_eq!(encounters[0].a.payoff, -2);
+        assert!(simulation.population.get(coop).is_none());
+        assert_eq!(simulation.lineage.get(coop).unwrap().died, Some(1));
+    }
+
+    #[test]
+    fn health_stops_at_zero() {
+        let (mut simulation, coop, greed) = coop_and_greed(1);
+        simulation.clock.advance();
+        let encounter = simulation.resolve(&[(coop, greed)])[0];
+        assert_eq!((encounter.a.payoff, encounter.a.healthpoints), (-1, 0));
+        assert!(encounter.a.died);
+        assert!(matches!(
+            simulation.events.events().last(),
+            Some(Event::Encounter { a, .. }) if a.healthpoints == 0
+        ));
+    }
+}
@@ -0,0 +1,192 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    agent::{Agent, AgentId},
+    clock::SimClock,
+    encounter::Cooldowns,
+    events::{Event, EventBus},
+    lineage::Lineage,
+    noise::Noise,
+    payoff::PayoffMatrix,
+    population::Population,
+    rng::SimRng,
+    simulation::Simulation,
+    stats::TimeSeries,
+    strategy::StrategyRegistry,
+};
+
+//everything needed to carry on a simulation exactly where it was left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationState {
+    pub seed: u64,
+    pub rng: SimRng,
+    pub clock: SimClock,
+    pub payoffs: PayoffMatrix,
+    //the registry the run used, strategies are saved by id so it must match on load
+    pub strategies: Vec<String>,
+    pub agents: Vec<Agent>,
+    pub next_id: AgentId,
+    pub cooldowns: Cooldowns,
+    pub stats: TimeSeries,
+    //every event of the run so far, a stream opened after loading writes them again first
+    pub events: Vec<Event>,
+    #[serde(default)]
+    pub lineage: Lineage,
+    #[serde(default)]
+    pub noise: Noise,
+}
+
+//a saved run, the simulation plus whatever the front end keeps about its arena
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot<A> {
+    pub simulation: SimulationState,
+    pub arena: A,
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    //the snapshot was taken with a different set of strategies
+    Strategies(Vec<String>),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "could not access the snapshot: {e}"),
+            SnapshotError::Format(e) => write!(f, "invalid snapshot: {e}"),
+            SnapshotError::Strategies(saved) => write!(
+                f,
+                "the snapshot was saved with different strategies: {}",
+                saved.join(", ")
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl<A: Serialize> Snapshot<A> {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let mut file = BufWriter::new(File::create(path).map_err(SnapshotError::Io)?);
+        serde_json::to_writer(&mut file, self).map_err(SnapshotError::Format)?;
+        file.flush().map_err(SnapshotError::Io)
+    }
+}
+
+impl<A: DeserializeOwned> Snapshot<A> {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        let file = BufReader::new(File::open(path).map_err(SnapshotError::Io)?);
+        serde_json::from_reader(file).map_err(SnapshotError::Format)
+    }
+}
+
+impl Simulation {
+    //captures the simulation with every event so far, the file they were streamed to is not
+    //part of it
+    pub fn save_state(&self) -> SimulationState {
+        SimulationState {
+            seed: self.seed,
+            rng: self.rng.clone(),
+            clock: self.clock.clone(),
+            payoffs: self.population.payoffs,
+            strategies: self
+                .population
+                .strategies
+                .iter()
+                .map(|(_, s)| s.name().to_owned())
+                .collect(),
+            agents: self.population.agents().cloned().collect(),
+            next_id: self.population.next_id(),
+            cooldowns: self.cooldowns.clone(),
+            stats: self.stats.clone(),
+            events: self.events.events().to_vec(),
+            lineage: self.lineage.clone(),
+            noise: self.population.noise,
+        }
+    }
+
+    //carries on a saved simulation, `strategies` has to hold the same strategies in the same
+    //order as when it was saved
+    pub fn from_state(
+        state: SimulationState,
+        strategies: Arc<StrategyRegistry>,
+    ) -> Result<Self, SnapshotError> {
+        let matches = state.strategies.len() == strategies.len()
+            && strategies
+                .iter()
+                .zip(&state.strategies)
+                .all(|((_, s), saved)| s.name() == saved);
+        if !matches {
+            return Err(SnapshotError::Strategies(state.strategies));
+        }
+
+        let mut simulation = Simulation::new(state.seed, state.payoffs, strategies.clone());
+        simulation.rng = state.rng;
+        simulation.clock = state.clock;
+        simulation.population =
+            Population::from_agents(state.payoffs, strategies, state.agents, state.next_id);
+        simulation.population.noise = state.noise;
+        simulation.cooldowns = state.cooldowns;
+        simulation.stats = state.stats;
+        simulation.events = EventBus::from_events(state.events);
+        simulation.lineage = state.lineage;
+        //snapshots from before lineages were kept start the tree from whoever is alive
+        if simulation.lineage.is_empty() {
+            for agent in simulation.population.agents() {
+                simulation.lineage.record_birth(agent);
+            }
+        }
+        Ok(simulation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::{tests::scenario, HeadlessRun};
+
+    #[test]
+    fn a_restored_run_carries_on_the_same() {
+        let scenario = scenario();
+        let strategies = Arc::new(StrategyRegistry::default());
+        let mut original = HeadlessRun::new(&scenario, 5, strategies.clone()).unwrap();
+        original.run(600);
+        assert!(original.ended().is_none());
+
+        let saved = serde_json::to_string(&original.simulation.save_state()).unwrap();
+        let state = serde_json::from_str(&saved).unwrap();
+        let mut restored = HeadlessRun::new(&scenario, 5, strategies.clone()).unwrap();
+        restored.simulation = Simulation::from_state(state, strategies).unwrap();
+        restored.arena = original.arena.clone();
+
+        original.run(600);
+        restored.run(600);
+        assert_eq!(original.summary(), restored.summary());
+        assert_eq!(
+            serde_json::to_string(original.simulation.events.events()).unwrap(),
+            serde_json::to_string(restored.simulation.events.events()).unwrap()
+        );
+        assert_eq!(original.simulation.lineage, restored.simulation.lineage);
+    }
+
+    #[test]
+    fn other_strategies_are_refused() {
+        let state = Simulation::default().save_state();
+        let mut registry = StrategyRegistry::empty();
+        registry.register(crate::strategies::TitForTat);
+        assert!(matches!(
+            Simulation::from_state(state, Arc::new(registry)),
+            Err(SnapshotError::Strategies(_))
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    encounter::{Encounter, Side},
+    population::Population,
+    public_goods::Gathering,
+    strategy::{Move, StrategyId, StrategyRegistry},
+};
+
+//ticks between samples unless told otherwise, one per simulated second
+pub const DEFAULT_SAMPLE_INTERVAL: u64 = 60;
+
+//the state of one strategy at a sampled tick, births, deaths and the cooperation rate
+//cover the ticks since the previous sample
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub tick: u64,
+    pub strategy: StrategyId,
+    pub alive: usize,
+    pub total_hp: i64,
+    pub mean_hp: f64,
+    pub births: u64,
+    pub deaths: u64,
+    pub cooperation_rate: f64,
+}
+
+//what happened to one strategy since the last sample
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct Window {
+    births: u64,
+    deaths: u64,
+    moves: u64,
+    cooperations: u64,
+}
+
+//the extremes of one strategy over a whole run, tracked every tick
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Record {
+    pub peak_alive: usize,
+    pub peak_tick: u64,
+    //the tick the last agent of the strategy died, none while any are alive
+    pub extinct_at: Option<u64>,
+}
+
+//per strategy population statistics sampled every few ticks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeSeries {
+    pub interval: u64,
+    samples: Vec<Sample>,
+    window: Vec<Window>,
+    records: Vec<Record>,
+    //alive counts at the last tracked tick and the tick they last changed
+    alive: Vec<usize>,
+    last_change: u64,
+}
+
+impl Default for TimeSeries {
+    fn default() -> Self {
+        Self::new(DEFAULT_SAMPLE_INTERVAL)
+    }
+}
+
+impl TimeSeries {
+    pub fn new(interval: u64) -> Self {
+        Self {
+            interval: interval.max(1),
+            samples: Vec::new(),
+            window: Vec::new(),
+            records: Vec::new(),
+            alive: Vec::new(),
+            last_change: 0,
+        }
+    }
+
+    fn window_mut(&mut self, strategy: StrategyId) -> &mut Window {
+        if self.window.len() <= strategy {
+            self.window.resize(strategy + 1, Window::default());
+        }
+        &mut self.window[strategy]
+    }
+
+    pub fn record_birth(&mut self, strategy: StrategyId) {
+        self.window_mut(strategy).births += 1;
+    }
+
+    pub fn record_encounter(&mut self, encounter: &Encounter) {
+        self.record_sides(encounter.sides());
+    }
+
+    pub fn record_gathering(&mut self, gathering: &Gathering) {
+        self.record_sides(&gathering.members);
+    }
+
+    fn record_sides<'a>(&mut self, sides: impl IntoIterator<Item = &'a Side>) {
+        for side in sides {
+            let window = self.window_mut(side.strategy);
+            window.moves += 1;
+            if side.played == Move::Cooperate {
+                window.cooperations += 1;
+            }
+            if side.died {
+                window.deaths += 1;
+            }
+        }
+    }
+
+    //updates the peaks, extinctions and stability of every strategy, call once per tick
+    pub fn track(&mut self, tick: u64, population: &Population) {
+        let alive: Vec<_> = population
+            .strategies
+            .ids()
+            .map(|strategy| population.count(strategy))
+            .collect();
+        if alive != self.alive {
+            self.last_change = tick;
+        }
+        self.records.resize(alive.len(), Record::default());
+        for (record, &count) in self.records.iter_mut().zip(&alive) {
+            if count > record.peak_alive {
+                record.peak_alive = count;
+                record.peak_tick = tick;
+            }
+            if count > 0 {
+                record.extinct_at = None;
+            } else if record.peak_alive > 0 && record.extinct_at.is_none() {
+                record.extinct_at = Some(tick);
+            }
+        }
+        self.alive = alive;
+    }
+
+    pub fn record(&self, strategy: StrategyId) -> Record {
+        self.records.get(strategy).copied().unwrap_or_default()
+    }
+
+    //the tick the alive count of any strategy last changed
+    pub fn last_change(&self) -> u64 {
+        self.last_change
+    }
+
+    //takes a sample if `tick` falls on the interval
+    pub fn sample_if_due(&mut self, tick: u64, population: &Population) {
+        if tick.is_multiple_of(self.interval) {
+            self.sample(tick, population);
+        }
+    }
+
+    //adds a sample of every registered strategy and starts a new window
+    pub fn sample(&mut self, tick: u64, population: &Population) {
+        for strategy in population.strategies.ids() {
+            let alive = population.count(strategy);
+            let total_hp = population.total_hp(strategy);
+            let window = std::mem::take(self.window_mut(strategy));
+            self.samples.push(Sample {
+                tick,
+                strategy,
+                alive,
+                total_hp,
+                mean_hp: if alive > 0 {
+                    total_hp as f64 / alive as f64
+                } else {
+                    0.0
+                },
+                births: window.births,
+                deaths: window.deaths,
+                cooperation_rate: if window.moves > 0 {
+                    window.cooperations as f64 / window.moves as f64
+                } else {
+                    0.0
+                },
+            });
+        }
+    }
+
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+
+    //the samples of a single strategy, oldest first
+    pub fn strategy_samples(&self, strategy: StrategyId) -> impl Iterator<Item = &Sample> {
+        self.samples.iter().filter(move |s| s.strategy == strategy)
+    }
+
+    //writes every sample as csv, one row per strategy per sampled tick
+    pub fn write_csv(
+        &self,
+        writer: &mut impl Write,
+        seed: u64,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        writeln!(
+            writer,
+            "seed,tick,strategy,alive,total_hp,mean_hp,births,deaths,cooperation_rate"
+        )?;
+        for sample in &self.samples {
+            let name = strategies
+                .get(sample.strategy)
+                .map_or_else(|| sample.strategy.to_string(), |s| s.name().to_owned());
+            writeln!(
+                writer,
+                "{},{},{},{},{},{:.3},{},{},{:.3}",
+                seed,
+                sample.tick,
+                csv_field(&name),
+                sample.alive,
+                sample.total_hp,
+                sample.mean_hp,
+                sample.births,
+                sample.deaths,
+                sample.cooperation_rate
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn export_csv(
+        &self,
+        path: impl AsRef<Path>,
+        seed: u64,
+        strategies: &StrategyRegistry,
+    ) -> io::Result<()> {
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut file, seed, strategies)?;
+        file.flush()
+    }
+}
+
+//quotes a csv field when it holds a separator or a quote
+pub fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::Simulation;
+
+    #[test]
+    fn writes_a_row_per_strategy_and_sample() {
+        let mut simulation = Simulation::default();
+        let coop = simulation.spawn(0, 10);
+        let greed = simulation.spawn(1, 10);
+        simulation.finish_tick();
+        simulation.clock.advance();
+        simulation.resolve(&[(coop, greed)]);
+        simulation.final_sample();
+
+        let mut out = Vec::new();
+        let strategies = simulation.population.strategies.clone();
+        simulation
+            .stats
+            .write_csv(&mut out, 7, &strategies)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "seed,tick,strategy,alive,total_hp,mean_hp,births,deaths,cooperation_rate"
+        );
+        assert_eq!(lines.len(), 1 + 2 * strategies.len());
+        assert_eq!(lines[1], "7,0,Coop,1,10,10.000,1,0,0.000");
+        assert_eq!(lines[2], "7,0,Greed,1,10,10.000,1,0,0.000");
+        let tick_one = 1 + strategies.len();
+        assert_eq!(lines[tick_one], "7,1,Coop,1,8,8.000,0,0,1.000");
+        assert_eq!(lines[tick_one + 1], "7,1,Greed,1,13,13.000,0,0,0.000");
+    }
+
+    #[test]
+    fn deaths_are_counted_from_the_encounters() {
+        let mut simulation = Simulation::default();
+        let coop = simulation.spawn(0, 2);
+        let greed = simulation.spawn(1, 10);
+        simulation.finish_tick();
+        simulation.clock.advance();
+        simulation.resolve(&[(coop, greed)]);
+        simulation.final_sample();
+        let deaths = |strategy| {
+            simulation
+                .stats
+                .strategy_samples(strategy)
+                .last()
+                .map(|s| (s.tick, s.deaths))
+        };
+        assert_eq!(deaths(0), Some((1, 1)));
+        assert_eq!(deaths(1), Some((1, 0)));
+    }
+
+    #[test]
+    fn quotes_csv_fields() {
+        assert_eq!(csv_field("TFT"), "TFT");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
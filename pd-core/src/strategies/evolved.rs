@@ -0,0 +1,53 @@
+use std::{fs, io, path::Path};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    genome::Genome,
+    memory::History,
+    payoff::PayoffMatrix,
+    scenario::ScenarioError,
+    strategy::{Move, Strategy},
+};
+
+//a strategy that plays a genome's lookup table, saved by `pd-sim evolve` or written by hand
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evolved {
+    pub name: String,
+    pub genome: Genome,
+    pub color: [u8; 3],
+}
+
+impl Evolved {
+    //reads a ron list of genome strategies
+    pub fn load_all(path: impl AsRef<Path>) -> Result<Vec<Self>, ScenarioError> {
+        let text = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        ron::from_str(&text).map_err(ScenarioError::Parse)
+    }
+
+    //writes a list that `load_all` reads back
+    pub fn save_all(path: impl AsRef<Path>, strategies: &[Self]) -> io::Result<()> {
+        let text = ron::ser::to_string_pretty(strategies, ron::ser::PrettyConfig::default())
+            .map_err(io::Error::other)?;
+        fs::write(path, text + "\n")
+    }
+}
+
+impl Strategy for Evolved {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self) -> [u8; 3] {
+        self.color
+    }
+
+    fn genome(&self) -> Option<Genome> {
+        Some(self.genome)
+    }
+
+    fn decide(&self, history: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        self.genome.decide(history)
+    }
+}
@@ -0,0 +1,103 @@
+use std::{fs, path::Path};
+
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    memory::History,
+    payoff::PayoffMatrix,
+    scenario::ScenarioError,
+    strategy::{Move, Strategy},
+};
+
+//cooperates with a fixed probability whatever the opponent did
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mixed {
+    pub name: String,
+    //the chance of cooperating, from 0 to 1
+    pub cooperation: f64,
+    pub color: [u8; 3],
+}
+
+impl Mixed {
+    //the coin flipper, cooperating half the time
+    pub fn random() -> Self {
+        Self {
+            name: "Random".to_owned(),
+            cooperation: 0.5,
+            color: [160, 160, 160],
+        }
+    }
+
+    //reads a ron list of mixed strategies, every cooperation chance has to be from 0 to 1
+    pub fn load_all(path: impl AsRef<Path>) -> Result<Vec<Self>, ScenarioError> {
+        let text = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        let strategies: Vec<Self> = ron::from_str(&text).map_err(ScenarioError::Parse)?;
+        match strategies
+            .iter()
+            .find(|s| !(0.0..=1.0).contains(&s.cooperation))
+        {
+            Some(strategy) => Err(ScenarioError::Cooperation(strategy.name.clone())),
+            None => Ok(strategies),
+        }
+    }
+}
+
+impl Strategy for Mixed {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn color(&self) -> [u8; 3] {
+        self.color
+    }
+
+    fn decide(&self, _: Option<&History>, _: &PayoffMatrix, rng: &mut dyn RngCore) -> Move {
+        if rng.random_bool(self.cooperation.clamp(0.0, 1.0)) {
+            Move::Cooperate
+        } else {
+            Move::Defect
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    fn cooperations(mixed: &Mixed, seed: u64) -> usize {
+        let mut rng = rng::seeded(seed);
+        (0..10_000)
+            .filter(|_| mixed.decide(None, &PayoffMatrix::default(), &mut rng) == Move::Cooperate)
+            .count()
+    }
+
+    #[test]
+    fn cooperates_with_its_chance() {
+        let mut mixed = Mixed::random();
+        assert!((cooperations(&mixed, 1) as i64 - 5_000).abs() < 300);
+        mixed.cooperation = 0.9;
+        assert!((cooperations(&mixed, 1) as i64 - 9_000).abs() < 300);
+        mixed.cooperation = 0.0;
+        assert_eq!(cooperations(&mixed, 1), 0);
+        mixed.cooperation = 1.0;
+        assert_eq!(cooperations(&mixed, 1), 10_000);
+    }
+
+    #[test]
+    fn same_seed_same_moves() {
+        assert_eq!(
+            cooperations(&Mixed::random(), 7),
+            cooperations(&Mixed::random(), 7)
+        );
+    }
+
+    #[test]
+    fn loads_the_example_file() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../data/mixed.ron");
+        let mixed = Mixed::load_all(path).unwrap();
+        assert!(!mixed.is_empty());
+        assert!(mixed.iter().all(|m| (0.0..=1.0).contains(&m.cooperation)));
+    }
+}
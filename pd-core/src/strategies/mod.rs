@@ -0,0 +1,13 @@
+//! The built in strategies, see `StrategyRegistry::default` for the spawn order.
+
+mod evolved;
+mod mixed;
+mod reactive;
+mod unconditional;
+
+pub use evolved::Evolved;
+pub use mixed::Mixed;
+pub use reactive::{
+    GenerousTitForTat, GrimTrigger, Pavlov, SuspiciousTitForTat, TitForTat, TitForTwoTats,
+};
+pub use unconditional::{AlwaysCooperate, AlwaysDefect};
@@ -0,0 +1,245 @@
+use rand::{Rng, RngCore};
+
+use crate::{
+    memory::History,
+    payoff::PayoffMatrix,
+    strategy::{Move, Strategy},
+};
+
+//copies the opponent's last move, cooperating first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TitForTat;
+
+//only defects after the opponent defected twice in a row
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TitForTwoTats;
+
+//cooperates until the opponent defects once, then defects forever
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GrimTrigger;
+
+//win-stay lose-shift, cooperates when both played the same last round
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pavlov;
+
+//tit for tat that sometimes forgives a defection
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GenerousTitForTat;
+
+//tit for tat that defects first
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SuspiciousTitForTat;
+
+impl Strategy for TitForTat {
+    fn name(&self) -> &str {
+        "TFT"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [0, 170, 0]
+    }
+
+    fn decide(&self, history: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        history
+            .and_then(|h| h.last())
+            .map_or(Move::Cooperate, |r| r.opponent)
+    }
+}
+
+impl Strategy for TitForTwoTats {
+    fn name(&self) -> &str {
+        "TF2T"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [120, 220, 120]
+    }
+
+    fn decide(&self, history: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        let defected = |n| {
+            history
+                .and_then(|h| h.nth_last(n))
+                .is_some_and(|r| r.opponent == Move::Defect)
+        };
+        if defected(0) && defected(1) {
+            Move::Defect
+        } else {
+            Move::Cooperate
+        }
+    }
+}
+
+impl Strategy for GrimTrigger {
+    fn name(&self) -> &str {
+        "Grim"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [60, 60, 60]
+    }
+
+    fn decide(&self, history: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        if history.is_some_and(|h| h.opponent_ever_defected()) {
+            Move::Defect
+        } else {
+            Move::Cooperate
+        }
+    }
+}
+
+impl Strategy for Pavlov {
+    fn name(&self) -> &str {
+        "Pavlov"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [230, 200, 0]
+    }
+
+    fn decide(&self, history: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        match history.and_then(|h| h.last()) {
+            Some(r) if r.own != r.opponent => Move::Defect,
+            _ => Move::Cooperate,
+        }
+    }
+}
+
+impl Strategy for GenerousTitForTat {
+    fn name(&self) -> &str {
+        "GTFT"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [0, 200, 200]
+    }
+
+    fn decide(
+        &self,
+        history: Option<&History>,
+        payoffs: &PayoffMatrix,
+        rng: &mut dyn RngCore,
+    ) -> Move {
+        match history.and_then(|h| h.last()) {
+            Some(r) if r.opponent == Move::Defect => {
+                if rng.random_bool(payoffs.generosity()) {
+                    Move::Cooperate
+                } else {
+                    Move::Defect
+                }
+            }
+            _ => Move::Cooperate,
+        }
+    }
+}
+
+impl Strategy for SuspiciousTitForTat {
+    fn name(&self) -> &str {
+        "STFT"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [160, 0, 200]
+    }
+
+    fn decide(&self, history: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        history
+            .and_then(|h| h.last())
+            .map_or(Move::Defect, |r| r.opponent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng, strategy::Move::*};
+
+    //what a strategy plays after the given rounds, oldest first as (own, opponent)
+    fn decide_after(strategy: &dyn Strategy, rounds: &[(Move, Move)]) -> Move {
+        let mut history = History::default();
+        for (own, opponent) in rounds {
+            history.record(*own, *opponent);
+        }
+        let history = Some(&history).filter(|h| !h.is_empty());
+        strategy.decide(history, &PayoffMatrix::default(), &mut rng::seeded(0))
+    }
+
+    #[test]
+    fn tit_for_tat_copies_the_last_move() {
+        assert_eq!(decide_after(&TitForTat, &[]), Cooperate);
+        assert_eq!(decide_after(&TitForTat, &[(Cooperate, Defect)]), Defect);
+        assert_eq!(
+            decide_after(&TitForTat, &[(Cooperate, Defect), (Defect, Cooperate)]),
+            Cooperate
+        );
+    }
+
+    #[test]
+    fn tit_for_two_tats_waits_for_two_defections() {
+        assert_eq!(decide_after(&TitForTwoTats, &[]), Cooperate);
+        assert_eq!(
+            decide_after(&TitForTwoTats, &[(Cooperate, Defect)]),
+            Cooperate
+        );
+        assert_eq!(
+            decide_after(&TitForTwoTats, &[(Cooperate, Defect), (Cooperate, Defect)]),
+            Defect
+        );
+        assert_eq!(
+            decide_after(&TitForTwoTats, &[(Cooperate, Defect), (Defect, Cooperate)]),
+            Cooperate
+        );
+    }
+
+    #[test]
+    fn grim_trigger_never_forgives() {
+        assert_eq!(decide_after(&GrimTrigger, &[]), Cooperate);
+        assert_eq!(
+            decide_after(&GrimTrigger, &[(Cooperate, Cooperate)]),
+            Cooperate
+        );
+        let mut rounds = vec![(Cooperate, Defect)];
+        //even once the defection is forgotten
+        rounds.extend([(Defect, Cooperate); crate::memory::MEMORY_LENGTH]);
+        assert_eq!(decide_after(&GrimTrigger, &rounds), Defect);
+    }
+
+    #[test]
+    fn pavlov_stays_after_a_win_and_shifts_after_a_loss() {
+        assert_eq!(decide_after(&Pavlov, &[]), Cooperate);
+        assert_eq!(decide_after(&Pavlov, &[(Cooperate, Cooperate)]), Cooperate);
+        assert_eq!(decide_after(&Pavlov, &[(Defect, Defect)]), Cooperate);
+        assert_eq!(decide_after(&Pavlov, &[(Cooperate, Defect)]), Defect);
+        assert_eq!(decide_after(&Pavlov, &[(Defect, Cooperate)]), Defect);
+    }
+
+    #[test]
+    fn generous_tit_for_tat_forgives_some_defections() {
+        assert_eq!(decide_after(&GenerousTitForTat, &[]), Cooperate);
+        assert_eq!(
+            decide_after(&GenerousTitForTat, &[(Defect, Cooperate)]),
+            Cooperate
+        );
+        let payoffs = PayoffMatrix::default();
+        let mut history = History::default();
+        history.record(Cooperate, Defect);
+        let mut rng = rng::seeded(1);
+        let forgiven = (0..10_000)
+            .filter(|_| GenerousTitForTat.decide(Some(&history), &payoffs, &mut rng) == Cooperate)
+            .count();
+        let expected = payoffs.generosity() * 10_000.0;
+        assert!((forgiven as f64 - expected).abs() < 300.0, "{forgiven}");
+    }
+
+    #[test]
+    fn suspicious_tit_for_tat_opens_with_a_defection() {
+        assert_eq!(decide_after(&SuspiciousTitForTat, &[]), Defect);
+        assert_eq!(
+            decide_after(&SuspiciousTitForTat, &[(Defect, Cooperate)]),
+            Cooperate
+        );
+        assert_eq!(
+            decide_after(&SuspiciousTitForTat, &[(Cooperate, Defect)]),
+            Defect
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use rand::RngCore;
+
+use crate::{
+    memory::History,
+    payoff::PayoffMatrix,
+    strategy::{Move, Strategy},
+};
+
+const COOPERATIVE_SPRITE_PATH: &str = "data/Sprites/bugster_cooperative.png";
+const GREEDY_SPRITE_PATH: &str = "data/Sprites/bugster_greedy.png";
+
+//the original cooperative bugster, always cooperates
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysCooperate;
+
+//the original greedy bugster, always defects
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlwaysDefect;
+
+impl Strategy for AlwaysCooperate {
+    fn name(&self) -> &str {
+        "Coop"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [0, 0, 220]
+    }
+
+    fn sprite(&self) -> Option<&str> {
+        Some(COOPERATIVE_SPRITE_PATH)
+    }
+
+    fn decide(&self, _: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        Move::Cooperate
+    }
+}
+
+impl Strategy for AlwaysDefect {
+    fn name(&self) -> &str {
+        "Greed"
+    }
+
+    fn color(&self) -> [u8; 3] {
+        [220, 0, 0]
+    }
+
+    fn sprite(&self) -> Option<&str> {
+        Some(GREEDY_SPRITE_PATH)
+    }
+
+    fn decide(&self, _: Option<&History>, _: &PayoffMatrix, _: &mut dyn RngCore) -> Move {
+        Move::Defect
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    #[test]
+    fn ignore_the_opponent() {
+        let mut history = History::default();
+        history.record(Move::Cooperate, Move::Defect);
+        let (payoffs, mut rng) = (PayoffMatrix::default(), rng::seeded(0));
+        for history in [None, Some(&history)] {
+            assert_eq!(
+                AlwaysCooperate.decide(history, &payoffs, &mut rng),
+                Move::Cooperate
+            );
+            assert_eq!(
+                AlwaysDefect.decide(history, &payoffs, &mut rng),
+                Move::Defect
+            );
+        }
+    }
+}
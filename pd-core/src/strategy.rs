@@ -0,0 +1,115 @@
+use std::{fmt::Debug, sync::Arc};
+
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::{genome::Genome, memory::History, payoff::PayoffMatrix, strategies};
+
+//the move an agent plays in a single encounter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Move {
+    Cooperate,
+    Defect,
+}
+
+impl Move {
+    pub fn opposite(self) -> Move {
+        match self {
+            Move::Cooperate => Move::Defect,
+            Move::Defect => Move::Cooperate,
+        }
+    }
+}
+
+//index of a strategy in the registry
+pub type StrategyId = usize;
+
+//a behaviour an agent can follow, implement this and register it to add a new strategy
+pub trait Strategy: Debug + Send + Sync {
+    //short name shown in the hud, the spawn menu and exported results
+    fn name(&self) -> &str;
+
+    //the rgb tint used for this strategy's sprite and hud entries
+    fn color(&self) -> [u8; 3];
+
+    //a sprite made for this strategy, strategies without one tint the base sprite
+    fn sprite(&self) -> Option<&str> {
+        None
+    }
+
+    //the lookup table behind a genome strategy, agents following one carry their own copy
+    //which can change when they reproduce
+    fn genome(&self) -> Option<Genome> {
+        None
+    }
+
+    //picks the move to play given what is remembered about the opponent
+    fn decide(
+        &self,
+        history: Option<&History>,
+        payoffs: &PayoffMatrix,
+        rng: &mut dyn RngCore,
+    ) -> Move;
+}
+
+//every strategy agents can be spawned with
+#[derive(Debug, Clone)]
+pub struct StrategyRegistry {
+    strategies: Vec<Arc<dyn Strategy>>,
+}
+
+impl Default for StrategyRegistry {
+    //the built in strategies
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(strategies::AlwaysCooperate);
+        registry.register(strategies::AlwaysDefect);
+        registry.register(strategies::TitForTat);
+        registry.register(strategies::TitForTwoTats);
+        registry.register(strategies::GrimTrigger);
+        registry.register(strategies::Pavlov);
+        registry.register(strategies::GenerousTitForTat);
+        registry.register(strategies::SuspiciousTitForTat);
+        registry.register(strategies::Mixed::random());
+        registry
+    }
+}
+
+impl StrategyRegistry {
+    pub fn empty() -> Self {
+        Self {
+            strategies: Vec::new(),
+        }
+    }
+
+    pub fn register(&mut self, strategy: impl Strategy + 'static) -> StrategyId {
+        self.strategies.push(Arc::new(strategy));
+        self.strategies.len() - 1
+    }
+
+    pub fn get(&self, id: StrategyId) -> Option<&dyn Strategy> {
+        self.strategies.get(id).map(|s| s.as_ref())
+    }
+
+    //looks a strategy up by its name
+    pub fn find(&self, name: &str) -> Option<StrategyId> {
+        self.strategies.iter().position(|s| s.name() == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (StrategyId, &dyn Strategy)> {
+        self.strategies.iter().map(|s| s.as_ref()).enumerate()
+    }
+
+    pub fn ids(&self) -> std::ops::Range<StrategyId> {
+        0..self.strategies.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.strategies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+}
@@ -0,0 +1,430 @@
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    outcome::RunSummary,
+    payoff::PayoffMatrix,
+    scenario::{parse, Scenario, ScenarioError},
+    stats::csv_field,
+};
+
+//the spawn counts a sweep tries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SpawnAxis {
+    //every set of counts as written
+    Counts(Vec<BTreeMap<String, i64>>),
+    //`total` agents split between two strategies in `steps` even steps, from all `first` to
+    //all `second`
+    Mix {
+        first: String,
+        second: String,
+        total: i64,
+        steps: u32,
+    },
+}
+
+//a grid of scenarios, every spawn setting crossed with every payoff matrix, each run once per
+//seed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sweep {
+    //what every run starts from, the axes replace its spawn counts and payoffs
+    #[serde(default)]
+    pub scenario: Scenario,
+    //how long every run lasts at most
+    pub ticks: u64,
+    //runs per cell use the seeds first_seed, first_seed + 1, ...
+    #[serde(default)]
+    pub first_seed: u64,
+    pub replicates: u64,
+    //left out, the scenario's own spawn counts and payoffs are used
+    #[serde(default)]
+    pub spawn: Option<SpawnAxis>,
+    #[serde(default)]
+    pub payoffs: Vec<PayoffMatrix>,
+}
+
+//one combination of the sweep's axes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub index: usize,
+    pub spawn: BTreeMap<String, i64>,
+    pub payoffs: PayoffMatrix,
+}
+
+//a mean with the half width of its 95% confidence interval
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Estimate {
+    pub mean: f64,
+    pub ci: f64,
+}
+
+//how one strategy fared over every run of a cell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CellStats {
+    pub cell: usize,
+    pub strategy: String,
+    pub runs: usize,
+    pub alive: Estimate,
+    pub total_hp: Estimate,
+    //the share of runs the strategy was still alive at the end
+    pub survival: f64,
+    //the share of runs the strategy won
+    pub wins: f64,
+    //how long the runs lasted
+    pub ticks: Estimate,
+}
+
+impl Sweep {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let text = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        Self::from_ron(&text)
+    }
+
+    //parses a sweep and checks its base scenario and that every payoff matrix in it is a valid
+    //game of the base scenario's type
+    pub fn from_ron(text: &str) -> Result<Self, ScenarioError> {
+        let sweep: Sweep = parse(text)?;
+        sweep.scenario.validate()?;
+        for payoffs in &sweep.payoffs {
+            payoffs
+                .validate_as(sweep.scenario.game)
+                .map_err(ScenarioError::Payoffs)?;
+        }
+        Ok(sweep)
+    }
+
+    //every combination of spawn counts and payoffs, spawn counts vary slowest
+    pub fn cells(&self) -> Vec<Cell> {
+        let spawns = match &self.spawn {
+            None => vec![self.scenario.spawn.clone()],
+            Some(SpawnAxis::Counts(counts)) => counts.clone(),
+            Some(SpawnAxis::Mix {
+                first,
+                second,
+                total,
+                steps,
+            }) => {
+                let steps = (*steps).max(2) as i64;
+                (0..steps)
+                    .map(|step| {
+                        let count = total * (steps - 1 - step) / (steps - 1);
+                        BTreeMap::from([(first.clone(), count), (second.clone(), total - count)])
+                    })
+                    .collect()
+            }
+        };
+        let payoffs = if self.payoffs.is_empty() {
+            vec![self.scenario.payoffs()]
+        } else {
+            self.payoffs.clone()
+        };
+
+        spawns
+            .iter()
+            .flat_map(|spawn| payoffs.iter().map(move |payoffs| (spawn, payoffs)))
+            .enumerate()
+            .map(|(index, (spawn, payoffs))| Cell {
+                index,
+                spawn: spawn.clone(),
+                payoffs: *payoffs,
+            })
+            .collect()
+    }
+
+    pub fn seeds(&self) -> impl Iterator<Item = u64> {
+        let first_seed = self.first_seed;
+        (0..self.replicates).map(move |i| first_seed.wrapping_add(i))
+    }
+
+    //the scenario a cell runs
+    pub fn scenario(&self, cell: &Cell) -> Scenario {
+        Scenario {
+            spawn: cell.spawn.clone(),
+            payoffs: Some(cell.payoffs),
+            ..self.scenario.clone()
+        }
+    }
+}
+
+impl Estimate {
+    //the mean of the values and its 95% confidence interval from Student's t distribution,
+    //a single value has no interval
+    pub fn of(values: &[f64]) -> Self {
+        let n = values.len();
+        if n == 0 {
+            return Self { mean: 0.0, ci: 0.0 };
+        }
+        let mean = values.iter().sum::<f64>() / n as f64;
+        if n == 1 {
+            return Self { mean, ci: 0.0 };
+        }
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        Self {
+            mean,
+            ci: t_critical(n - 1) * (variance / n as f64).sqrt(),
+        }
+    }
+}
+
+//the two sided 95% critical value of Student's t distribution
+fn t_critical(degrees_of_freedom: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+        2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+        2.052, 2.048, 2.045, 2.042,
+    ];
+    match degrees_of_freedom {
+        0 => f64::NAN,
+        df if df <= TABLE.len() => TABLE[df - 1],
+        df if df <= 60 => 2.000,
+        df if df <= 120 => 1.980,
+        _ => 1.960,
+    }
+}
+
+//sums up the runs of every cell per strategy, `runs` pairs each summary with its cell
+pub fn aggregate(cells: &[Cell], runs: &[(usize, RunSummary)]) -> Vec<CellStats> {
+    let mut stats = Vec::new();
+    for cell in cells {
+        let summaries: Vec<_> = runs
+            .iter()
+            .filter(|(index, _)| *index == cell.index)
+            .map(|(_, summary)| summary)
+            .collect();
+        let ticks: Vec<_> = summaries.iter().map(|s| s.ticks as f64).collect();
+        let mut strategies: Vec<&str> = summaries
+            .iter()
+            .flat_map(|s| s.strategies.iter().map(|s| s.strategy.as_str()))
+            .collect();
+        strategies.sort_unstable();
+        strategies.dedup();
+
+        for strategy in strategies {
+            let results: Vec<_> = summaries
+                .iter()
+                .filter_map(|s| s.strategies.iter().find(|s| s.strategy == strategy))
+                .collect();
+            let share = |count: usize| count as f64 / summaries.len().max(1) as f64;
+            stats.push(CellStats {
+                cell: cell.index,
+                strategy: strategy.to_owned(),
+                runs: summaries.len(),
+                alive: Estimate::of(&results.iter().map(|s| s.alive as f64).collect::<Vec<_>>()),
+                total_hp: Estimate::of(
+                    &results
+                        .iter()
+                        .map(|s| s.total_hp as f64)
+                        .collect::<Vec<_>>(),
+                ),
+                survival: share(results.iter().filter(|s| s.alive > 0).count()),
+                wins: share(
+                    summaries
+                        .iter()
+                        .filter(|s| s.winner.as_deref() == Some(strategy))
+                        .count(),
+                ),
+                ticks: Estimate::of(&ticks),
+            });
+        }
+    }
+    stats
+}
+
+//writes the aggregated table, one row per cell and strategy with the cell's settings
+pub fn write_csv(writer: &mut impl Write, cells: &[Cell], stats: &[CellStats]) -> io::Result<()> {
+    writeln!(
+        writer,
+        "cell,spawn,reward,temptation,sucker,punishment,strategy,runs,alive_mean,alive_ci,\
+         total_hp_mean,total_hp_ci,survival,wins,ticks_mean,ticks_ci"
+    )?;
+    for row in stats {
+        let Some(cell) = cells.iter().find(|c| c.index == row.cell) else {
+            continue;
+        };
+        let spawn = cell
+            .spawn
+            .iter()
+            .map(|(name, count)| format!("{name}={count}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let p = &cell.payoffs;
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.1},{:.1}",
+            cell.index,
+            csv_field(&spawn),
+            p.reward,
+            p.temptation,
+            p.sucker,
+            p.punishment,
+            csv_field(&row.strategy),
+            row.runs,
+            row.alive.mean,
+            row.alive.ci,
+            row.total_hp.mean,
+            row.total_hp.ci,
+            row.survival,
+            row.wins,
+            row.ticks.mean,
+            row.ticks.ci
+        )?;
+    }
+    Ok(())
+}
+
+pub fn export_csv(path: impl AsRef<Path>, cells: &[Cell], stats: &[CellStats]) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    write_csv(&mut file, cells, stats)?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{outcome::StrategySummary, payoff::GameType};
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn estimates_use_students_t() {
+        assert_eq!(Estimate::of(&[]), Estimate { mean: 0.0, ci: 0.0 });
+        assert_eq!(Estimate::of(&[5.0]), Estimate { mean: 5.0, ci: 0.0 });
+        //a standard error of 1 with one degree of freedom
+        let two = Estimate::of(&[1.0, 3.0]);
+        assert!(close(two.mean, 2.0) && close(two.ci, 12.706), "{two:?}");
+        let five = Estimate::of(&[2.0, 4.0, 4.0, 4.0, 6.0]);
+        assert!(close(five.mean, 4.0), "{five:?}");
+        assert!(close(five.ci, 2.776 * (2.0f64 / 5.0).sqrt()), "{five:?}");
+        //past 120 degrees of freedom the normal distribution's value is used
+        let values: Vec<_> = (0..200).map(|i| f64::from(i % 2 * 2)).collect();
+        let many = Estimate::of(&values);
+        assert!(close(many.mean, 1.0), "{many:?}");
+        assert!(close(many.ci, 1.96 * (1.0f64 / 199.0).sqrt()), "{many:?}");
+    }
+
+    #[test]
+    fn t_critical_values() {
+        assert!(t_critical(0).is_nan());
+        assert_eq!(t_critical(1), 12.706);
+        assert_eq!(t_critical(30), 2.042);
+        assert_eq!(t_critical(31), 2.000);
+        assert_eq!(t_critical(120), 1.980);
+        assert_eq!(t_critical(121), 1.960);
+    }
+
+    #[test]
+    fn a_mix_is_crossed_with_every_matrix() {
+        let sweep = Sweep {
+            scenario: Scenario::default(),
+            ticks: 10,
+            first_seed: 0,
+            replicates: 1,
+            spawn: Some(SpawnAxis::Mix {
+                first: "Coop".to_owned(),
+                second: "Greed".to_owned(),
+                total: 10,
+                steps: 5,
+            }),
+            payoffs: vec![
+                PayoffMatrix::default(),
+                GameType::Snowdrift.preset().unwrap(),
+            ],
+        };
+        let cells = sweep.cells();
+        assert_eq!(cells.len(), 10);
+        assert!(cells.iter().enumerate().all(|(i, cell)| cell.index == i));
+        let coop: Vec<_> = cells.iter().step_by(2).map(|c| c.spawn["Coop"]).collect();
+        assert_eq!(coop, [10, 7, 5, 2, 0]);
+        assert!(cells
+            .iter()
+            .all(|c| c.spawn["Coop"] + c.spawn["Greed"] == 10));
+        assert_eq!(cells[0].spawn, cells[1].spawn);
+        assert_ne!(cells[0].payoffs, cells[1].payoffs);
+
+        //fewer than two steps still goes from one end to the other
+        let ends = Sweep {
+            spawn: Some(SpawnAxis::Mix {
+                first: "Coop".to_owned(),
+                second: "Greed".to_owned(),
+                total: 10,
+                steps: 0,
+            }),
+            payoffs: Vec::new(),
+            ..sweep
+        };
+        let cells = ends.cells();
+        assert_eq!(cells.len(), 2);
+        assert_eq!(cells[1].spawn["Greed"], 10);
+        assert_eq!(cells[1].payoffs, PayoffMatrix::default());
+    }
+
+    fn summary(winner: &str, ticks: u64, strategies: &[(&str, usize, i64)]) -> RunSummary {
+        RunSummary {
+            seed: 0,
+            game: GameType::PrisonersDilemma,
+            payoffs: PayoffMatrix::default(),
+            public_goods: None,
+            ticks,
+            reason: None,
+            winner: Some(winner.to_owned()),
+            strategies: strategies
+                .iter()
+                .map(|(strategy, alive, total_hp)| StrategySummary {
+                    strategy: (*strategy).to_owned(),
+                    alive: *alive,
+                    total_hp: *total_hp,
+                    peak_alive: *alive,
+                    peak_tick: 0,
+                    extinct_at: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn runs_are_summed_up_per_cell_and_strategy() {
+        let cell = |index| Cell {
+            index,
+            spawn: BTreeMap::new(),
+            payoffs: PayoffMatrix::default(),
+        };
+        let cells = [cell(0), cell(1), cell(2)];
+        let runs = [
+            (
+                0,
+                summary("Greed", 100, &[("Coop", 2, 10), ("Greed", 4, 30)]),
+            ),
+            (1, summary("Coop", 50, &[("Coop", 9, 90), ("Greed", 0, 0)])),
+            (
+                0,
+                summary("Greed", 300, &[("Coop", 0, 0), ("Greed", 6, 50)]),
+            ),
+        ];
+        let stats = aggregate(&cells, &runs);
+        //nothing for the cell without runs
+        assert_eq!(stats.len(), 4);
+        let coop = &stats[0];
+        assert_eq!(
+            (coop.cell, coop.strategy.as_str(), coop.runs),
+            (0, "Coop", 2)
+        );
+        assert!(close(coop.alive.mean, 1.0) && close(coop.alive.ci, 12.706));
+        assert!(close(coop.total_hp.mean, 5.0));
+        assert_eq!((coop.survival, coop.wins), (0.5, 0.0));
+        assert!(close(coop.ticks.mean, 200.0));
+        let greed = &stats[1];
+        assert_eq!(greed.strategy, "Greed");
+        assert_eq!((greed.survival, greed.wins), (1.0, 1.0));
+        assert!(close(greed.alive.mean, 5.0));
+        assert_eq!((stats[2].cell, stats[2].runs), (1, 1));
+        assert_eq!(stats[2].alive, Estimate { mean: 9.0, ci: 0.0 });
+    }
+}
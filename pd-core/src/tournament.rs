@@ -0,0 +1,399 @@
+use std::{
+    fmt, fs,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    memory::History,
+    noise::Noise,
+    payoff::{GameType, PayoffMatrix},
+    rng::{self, SimRng},
+    scenario::{parse, ScenarioError},
+    stats::csv_field,
+    strategy::{Move, Strategy, StrategyId, StrategyRegistry},
+};
+
+//the settings of a round robin tournament, read from a ron file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tournament {
+    //the most rounds a match lasts
+    pub rounds: u32,
+    //the chance another round follows each round, matches then end early at random so
+    //strategies can't count on a last round, left out every match lasts `rounds`
+    pub continuation: Option<f64>,
+    //the chance a move comes out the opposite of what the strategy picked
+    pub noise: f64,
+    //the chance a strategy remembers the opposite of what its opponent played
+    pub perception: f64,
+    //how many times every pairing is played, the results are averaged
+    pub repetitions: u32,
+    //the game the payoffs have to be, a prisoner's dilemma unless set
+    pub game: GameType,
+    //left out, the game's built in matrix is used
+    pub payoffs: Option<PayoffMatrix>,
+    //the strategies that enter by name, left empty every registered strategy does
+    pub strategies: Vec<String>,
+    pub seed: Option<u64>,
+}
+
+//one strategy's line in the final table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Standing {
+    pub rank: usize,
+    pub strategy: String,
+    //the mean payoff per round over every match
+    pub score: f64,
+    //the summed payoff over every match
+    pub total: i64,
+    //matches where it scored more than the opponent
+    pub wins: u32,
+    pub cooperation_rate: f64,
+}
+
+//the outcome of a tournament
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TournamentResult {
+    pub seed: u64,
+    pub strategies: Vec<String>,
+    //the mean payoff per round the row strategy got against the column strategy
+    pub matrix: Vec<Vec<f64>>,
+    //best first
+    pub standings: Vec<Standing>,
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self {
+            rounds: 200,
+            continuation: None,
+            noise: 0.0,
+            perception: 0.0,
+            repetitions: 1,
+            game: GameType::default(),
+            payoffs: None,
+            strategies: Vec::new(),
+            seed: None,
+        }
+    }
+}
+
+//what one side of a match scored
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Tally {
+    pub payoff: i64,
+    pub rounds: u64,
+    pub cooperations: u64,
+}
+
+impl Tournament {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let text = fs::read_to_string(path).map_err(ScenarioError::Io)?;
+        Self::from_ron(&text)
+    }
+
+    //parses a tournament and checks its payoffs are a valid game of its type
+    pub fn from_ron(text: &str) -> Result<Self, ScenarioError> {
+        let tournament: Tournament = parse(text)?;
+        tournament
+            .payoffs()
+            .validate_as(tournament.game)
+            .map_err(ScenarioError::Payoffs)?;
+        Ok(tournament)
+    }
+
+    //the payoffs every match is played with
+    pub fn payoffs(&self) -> PayoffMatrix {
+        self.game.or_preset(self.payoffs)
+    }
+
+    //the strategies that enter, in registry order
+    pub fn entrants(&self, registry: &StrategyRegistry) -> Result<Vec<StrategyId>, ScenarioError> {
+        if self.strategies.is_empty() {
+            return Ok(registry.ids().collect());
+        }
+        let mut entrants = self
+            .strategies
+            .iter()
+            .map(|name| {
+                registry
+                    .find(name)
+                    .ok_or_else(|| ScenarioError::UnknownStrategy(name.clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        entrants.sort_unstable();
+        entrants.dedup();
+        Ok(entrants)
+    }
+
+    //plays every entrant against every other and itself, the same decisions and payoffs an
+    //encounter in the arena uses, just without the arena
+    pub fn run(
+        &self,
+        seed: u64,
+        registry: &StrategyRegistry,
+    ) -> Result<TournamentResult, ScenarioError> {
+        let entrants = self.entrants(registry)?;
+        let mut rng = rng::seeded(seed);
+        let n = entrants.len();
+        let mut tallies = vec![vec![Tally::default(); n]; n];
+        let mut wins = vec![0; n];
+
+        for i in 0..n {
+            for j in i..n {
+                for _ in 0..self.repetitions {
+                    let (Some(a), Some(b)) = (registry.get(entrants[i]), registry.get(entrants[j]))
+                    else {
+                        continue;
+                    };
+                    let [a, b] = self.play_match(a, b, &mut rng);
+                    //against itself a strategy's cell only gets the first side, or every
+                    //self-play match would count twice
+                    let sides = [(i, j, a), (j, i, b)];
+                    let sides = if i == j { &sides[..1] } else { &sides[..] };
+                    for &(row, column, tally) in sides {
+                        let cell = &mut tallies[row][column];
+                        cell.payoff += tally.payoff;
+                        cell.rounds += tally.rounds;
+                        cell.cooperations += tally.cooperations;
+                    }
+                    if i != j {
+                        if a.payoff > b.payoff {
+                            wins[i] += 1;
+                        } else if b.payoff > a.payoff {
+                            wins[j] += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        let per_round = |tally: &Tally| tally.payoff as f64 / tally.rounds.max(1) as f64;
+        let strategies: Vec<String> = entrants
+            .iter()
+            .map(|id| {
+                registry
+                    .get(*id)
+                    .map_or_else(|| id.to_string(), |s| s.name().to_owned())
+            })
+            .collect();
+        let matrix = tallies
+            .iter()
+            .map(|row| row.iter().map(per_round).collect())
+            .collect();
+        let mut standings: Vec<_> = tallies
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let total = row.iter().fold(Tally::default(), |sum, tally| Tally {
+                    payoff: sum.payoff + tally.payoff,
+                    rounds: sum.rounds + tally.rounds,
+                    cooperations: sum.cooperations + tally.cooperations,
+                });
+                Standing {
+                    rank: 0,
+                    strategy: strategies[i].clone(),
+                    score: per_round(&total),
+                    total: total.payoff,
+                    wins: wins[i],
+                    cooperation_rate: total.cooperations as f64 / total.rounds.max(1) as f64,
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| b.score.total_cmp(&a.score));
+        for (rank, standing) in standings.iter_mut().enumerate() {
+            standing.rank = rank + 1;
+        }
+
+        Ok(TournamentResult {
+            seed,
+            strategies,
+            matrix,
+            standings,
+        })
+    }
+
+    //plays one match, both sides decide from their own history of it
+    pub(crate) fn play_match(
+        &self,
+        strategy_a: &dyn Strategy,
+        strategy_b: &dyn Strategy,
+        rng: &mut SimRng,
+    ) -> [Tally; 2] {
+        let mut histories = [History::default(), History::default()];
+        let mut tallies = [Tally::default(); 2];
+        let payoffs = self.payoffs();
+        let noise = Noise {
+            execution: self.noise,
+            perception: self.perception,
+        };
+
+        for round in 0..self.rounds {
+            if round > 0 {
+                if let Some(continuation) = self.continuation {
+                    if !rng.random_bool(continuation.clamp(0.0, 1.0)) {
+                        break;
+                    }
+                }
+            }
+            //like in the arena, a strategy that hasn't met its opponent yet gets no history
+            let [history_a, history_b] = histories
+                .each_ref()
+                .map(|h| Some(h).filter(|h| !h.is_empty()));
+            let picked = [
+                strategy_a.decide(history_a, &payoffs, rng),
+                strategy_b.decide(history_b, &payoffs, rng),
+            ];
+            let played = picked.map(|own| noise.execute(own, rng));
+            for (side, opponent) in [(0, 1), (1, 0)] {
+                let seen = noise.perceive(played[opponent], rng);
+                histories[side].record(played[side], seen);
+                let tally = &mut tallies[side];
+                tally.payoff += payoffs.payoff(played[side], played[opponent]);
+                tally.rounds += 1;
+                tally.cooperations += u64::from(played[side] == Move::Cooperate);
+            }
+        }
+        tallies
+    }
+}
+
+impl TournamentResult {
+    //writes the ranked table as csv
+    pub fn write_standings(&self, writer: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "seed,rank,strategy,score,total,wins,cooperation_rate"
+        )?;
+        for s in &self.standings {
+            writeln!(
+                writer,
+                "{},{},{},{:.4},{},{},{:.3}",
+                self.seed,
+                s.rank,
+                csv_field(&s.strategy),
+                s.score,
+                s.total,
+                s.wins,
+                s.cooperation_rate
+            )?;
+        }
+        Ok(())
+    }
+
+    //writes the pairwise matrix as csv, rows are the strategy scoring
+    pub fn write_matrix(&self, writer: &mut impl Write) -> io::Result<()> {
+        let header: Vec<_> = self.strategies.iter().map(|s| csv_field(s)).collect();
+        writeln!(writer, "strategy,{}", header.join(","))?;
+        for (name, row) in self.strategies.iter().zip(&self.matrix) {
+            let row: Vec<_> = row.iter().map(|score| format!("{score:.4}")).collect();
+            writeln!(writer, "{},{}", csv_field(name), row.join(","))?;
+        }
+        Ok(())
+    }
+
+    //writes `<prefix>_standings.csv` and `<prefix>_matrix.csv` into a directory
+    pub fn export_csv(&self, dir: impl AsRef<Path>, prefix: &str) -> io::Result<()> {
+        let dir = dir.as_ref();
+        let mut standings =
+            BufWriter::new(File::create(dir.join(format!("{prefix}_standings.csv")))?);
+        self.write_standings(&mut standings)?;
+        standings.flush()?;
+        let mut matrix = BufWriter::new(File::create(dir.join(format!("{prefix}_matrix.csv")))?);
+        self.write_matrix(&mut matrix)?;
+        matrix.flush()
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self
+            .strategies
+            .iter()
+            .map(|s| s.len())
+            .max()
+            .unwrap_or(0)
+            .max(8);
+        writeln!(
+            f,
+            "{:>4}  {:<width$}  {:>8}  {:>5}  {:>6}",
+            "Rank", "Strategy", "Score", "Wins", "Coop"
+        )?;
+        for s in &self.standings {
+            writeln!(
+                f,
+                "{:>4}  {:<width$}  {:>8.3}  {:>5}  {:>5.1}%",
+                s.rank,
+                s.strategy,
+                s.score,
+                s.wins,
+                s.cooperation_rate * 100.0
+            )?;
+        }
+        writeln!(f)?;
+        write!(f, "{:<width$}", "")?;
+        for name in &self.strategies {
+            write!(f, "  {name:>8}")?;
+        }
+        writeln!(f)?;
+        for (name, row) in self.strategies.iter().zip(&self.matrix) {
+            write!(f, "{name:<width$}")?;
+            for score in row {
+                write!(f, "  {score:>8.3}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_every_pairing_once() {
+        let tournament = Tournament {
+            rounds: 10,
+            strategies: vec!["Coop".to_owned(), "Greed".to_owned()],
+            ..Tournament::default()
+        };
+        let result = tournament.run(1, &StrategyRegistry::default()).unwrap();
+        assert_eq!(result.matrix, [[2.0, -2.0], [3.0, -1.0]]);
+        let total = |name: &str| {
+            result
+                .standings
+                .iter()
+                .find(|s| s.strategy == name)
+                .map(|s| (s.total, s.score))
+                .unwrap()
+        };
+        //ten rounds against itself and ten against the other
+        assert_eq!(total("Coop"), (20 - 20, 0.0));
+        assert_eq!(total("Greed"), (30 - 10, 1.0));
+        assert_eq!(result.standings[0].strategy, "Greed");
+    }
+
+    #[test]
+    fn left_out_payoffs_are_the_games_preset() {
+        let tournament = Tournament::from_ron("(rounds: 10, game: StagHunt)").unwrap();
+        assert_eq!(Some(tournament.payoffs()), GameType::StagHunt.preset());
+    }
+
+    #[test]
+    fn unknown_entrants_are_refused() {
+        let tournament = Tournament {
+            strategies: vec!["Nobody".to_owned()],
+            ..Tournament::default()
+        };
+        assert!(matches!(
+            tournament.run(1, &StrategyRegistry::default()),
+            Err(ScenarioError::UnknownStrategy(name)) if name == "Nobody"
+        ));
+    }
+}
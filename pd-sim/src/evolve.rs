@@ -0,0 +1,39 @@
+use std::error::Error;
+
+use pd_core::{rng, strategies::Evolved, Evolution};
+
+use crate::{arg_value, output_dir, strategies, USAGE};
+
+//evolves genomes offline and writes how every generation did plus the fittest genomes as
+//strategies the game and pd-sim can load with `--genomes`
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let evolution_path = std::env::args().nth(2).ok_or(USAGE)?;
+    let evolution = Evolution::load(&evolution_path)?;
+    let seed = match arg_value("--seed") {
+        Some(seed) => seed.parse().map_err(|_| format!("invalid seed {seed}"))?,
+        None => evolution.seed.unwrap_or_else(rng::random_seed),
+    };
+    let dir = output_dir()?;
+
+    eprintln!(
+        "Evolving {} memory-{} genomes for {} generations with seed {seed}",
+        evolution.population, evolution.memory, evolution.generations
+    );
+    let result = evolution.run(seed, &strategies()?, |stats| {
+        if stats.generation.is_multiple_of(10) {
+            eprintln!(
+                "generation {}: best {:.3}, mean {:.3}",
+                stats.generation, stats.best_fitness, stats.mean_fitness
+            );
+        }
+    })?;
+    let history = dir.join(format!("evolution_seed{seed}.csv"));
+    result.export_csv(&history)?;
+    let genomes = dir.join(format!("genomes_seed{seed}.ron"));
+    let prefix = format!("M{}-s{seed}", evolution.memory);
+    Evolved::save_all(&genomes, &result.strategies(evolution.keep, &prefix))?;
+
+    print!("{result}");
+    eprintln!("Wrote {} and {}", history.display(), genomes.display());
+    Ok(())
+}
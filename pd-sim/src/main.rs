@@ -0,0 +1,155 @@
+//! Runs a scenario without a window and writes its results, for build servers and batch jobs.
+//!
+//! ```text
+//! pd-sim <scenario.ron> [--seed <n>] [--ticks <n>] [--output <dir>] [--replay]
+//! pd-sim sweep <sweep.ron> [--output <dir>] [--threads <n>]
+//! pd-sim tournament <tournament.ron> [--seed <n>] [--output <dir>]
+//! pd-sim evolve <evolution.ron> [--seed <n>] [--output <dir>]
+//! ```
+//!
+//! Every command also takes `--genomes <file>` to add the genome strategies saved by `evolve`
+//! and `--mixed <file>` to add strategies that cooperate with a set chance.
+use std::{error::Error, path::PathBuf, process::ExitCode, sync::Arc};
+
+use pd_core::{
+    rng,
+    strategies::{Evolved, Mixed},
+    HeadlessRun, Scenario, StrategyRegistry,
+};
+
+mod evolve;
+mod sweep;
+mod tournament;
+
+const RESULTS_DIR: &str = "results";
+const USAGE: &str =
+    "usage: pd-sim <scenario.ron> [--seed <n>] [--ticks <n>] [--output <dir>] [--replay]
+       pd-sim sweep <sweep.ron> [--output <dir>] [--threads <n>]
+       pd-sim tournament <tournament.ron> [--seed <n>] [--output <dir>]
+       pd-sim evolve <evolution.ron> [--seed <n>] [--output <dir>]
+every command also takes --genomes <file> and --mixed <file>";
+
+fn main() -> ExitCode {
+    let result = match std::env::args().nth(1).as_deref() {
+        Some("sweep") => sweep::run(),
+        Some("tournament") => tournament::run(),
+        Some("evolve") => evolve::run(),
+        _ => run(),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("pd-sim: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let scenario_path = std::env::args()
+        .nth(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .ok_or(USAGE)?;
+    let scenario = Scenario::load(&scenario_path)?;
+
+    //the command line wins over the scenario file
+    let seed = match arg_value("--seed") {
+        Some(seed) => seed.parse().map_err(|_| format!("invalid seed {seed}"))?,
+        None => scenario.seed.unwrap_or_else(rng::random_seed),
+    };
+    let ticks = match arg_value("--ticks") {
+        Some(ticks) => ticks
+            .parse()
+            .map_err(|_| format!("invalid tick count {ticks}"))?,
+        None => scenario
+            .end_conditions
+            .tick_limit
+            .ok_or("no tick count, pass --ticks or set a tick_limit in the scenario")?,
+    };
+    let dir = output_dir()?;
+    let name = |kind: &str, extension: &str| dir.join(format!("{kind}_seed{seed}.{extension}"));
+
+    let mut run = HeadlessRun::new(&scenario, seed, Arc::new(strategies()?))?;
+    let events = name("events", "jsonl");
+    run.simulation.events.stream_to(&events)?;
+    //replays hold every position of the run so they are only written when asked for
+    let replay = std::env::args()
+        .any(|arg| arg == "--replay")
+        .then(|| name("replay", "json"));
+    if replay.is_some() {
+        run.record_replay();
+    }
+    eprintln!("Running {scenario_path} with seed {seed} for up to {ticks} ticks");
+    run.run(ticks);
+
+    let simulation = &mut run.simulation;
+    simulation.events.flush()?;
+    if let Some(e) = simulation.events.take_error() {
+        return Err(format!("could not write {}: {e}", events.display()).into());
+    }
+    let timeseries = name("timeseries", "csv");
+    simulation.stats.export_csv(
+        &timeseries,
+        simulation.seed,
+        &simulation.population.strategies,
+    )?;
+    let lineage_tree = name("lineage", "nwk");
+    let lineage_edges = name("lineage", "csv");
+    simulation
+        .lineage
+        .export_newick(&lineage_tree, &simulation.population.strategies)?;
+    simulation.lineage.export_csv(
+        &lineage_edges,
+        simulation.seed,
+        &simulation.population.strategies,
+    )?;
+    let summary = run.summary();
+    let summary_path = name("summary", "json");
+    summary.export_json(&summary_path)?;
+    if let (Some(path), Some(recorder)) = (&replay, &run.recorder) {
+        recorder.replay().save(path)?;
+    }
+
+    print!("{summary}");
+    eprintln!(
+        "Wrote {}, {}, {}, {} and {}",
+        timeseries.display(),
+        events.display(),
+        summary_path.display(),
+        lineage_tree.display(),
+        lineage_edges.display()
+    );
+    if let Some(path) = replay {
+        eprintln!("Wrote {}", path.display());
+    }
+    Ok(())
+}
+
+//the value after a flag on the command line
+pub(crate) fn arg_value(flag: &str) -> Option<String> {
+    std::env::args().skip_while(|arg| arg != flag).nth(1)
+}
+
+//the built in strategies plus any genome strategies given with `--genomes` and mixed ones
+//given with `--mixed`
+pub(crate) fn strategies() -> Result<StrategyRegistry, Box<dyn Error>> {
+    let mut registry = StrategyRegistry::default();
+    if let Some(path) = arg_value("--genomes") {
+        for strategy in Evolved::load_all(&path)? {
+            registry.register(strategy);
+        }
+    }
+    if let Some(path) = arg_value("--mixed") {
+        for strategy in Mixed::load_all(&path)? {
+            registry.register(strategy);
+        }
+    }
+    Ok(registry)
+}
+
+//the directory given with `--output`, created if it doesn't exist yet
+pub(crate) fn output_dir() -> std::io::Result<PathBuf> {
+    let dir = arg_value("--output").map_or_else(|| PathBuf::from(RESULTS_DIR), PathBuf::from);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
@@ -0,0 +1,88 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use pd_core::{
+    stats::csv_field,
+    sweep::{self, Sweep},
+    HeadlessRun, RunSummary,
+};
+use rayon::prelude::*;
+
+use crate::{arg_value, output_dir, strategies, USAGE};
+
+//runs every cell of a sweep once per seed on all cores and writes the per run results and the
+//aggregated table
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let sweep_path = std::env::args().nth(2).ok_or(USAGE)?;
+    let sweep = Sweep::load(&sweep_path)?;
+    if let Some(threads) = arg_value("--threads") {
+        let threads = threads
+            .parse()
+            .map_err(|_| format!("invalid thread count {threads}"))?;
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
+    let dir = output_dir()?;
+
+    let strategies = Arc::new(strategies()?);
+    let cells = sweep.cells();
+    //check every cell before spending any time on runs
+    for cell in &cells {
+        sweep.scenario(cell).spawn_counts(&strategies)?;
+    }
+    let jobs: Vec<_> = cells
+        .iter()
+        .flat_map(|cell| sweep.seeds().map(move |seed| (cell, seed)))
+        .collect();
+    eprintln!(
+        "Running {} cells x {} seeds = {} runs on {} threads",
+        cells.len(),
+        sweep.replicates,
+        jobs.len(),
+        rayon::current_num_threads()
+    );
+
+    let done = AtomicUsize::new(0);
+    let runs: Vec<(usize, RunSummary)> = jobs
+        .par_iter()
+        .map(|(cell, seed)| {
+            let mut run = HeadlessRun::new(&sweep.scenario(cell), *seed, strategies.clone())?;
+            run.run(sweep.ticks);
+            let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+            if finished.is_multiple_of(10) || finished == jobs.len() {
+                eprintln!("{finished}/{} runs done", jobs.len());
+            }
+            Ok((cell.index, run.summary()))
+        })
+        .collect::<Result<_, pd_core::ScenarioError>>()?;
+
+    let runs_path = dir.join("sweep_runs.csv");
+    let mut file = BufWriter::new(File::create(&runs_path)?);
+    writeln!(file, "cell,seed,ticks,reason,winner")?;
+    for (cell, summary) in &runs {
+        writeln!(
+            file,
+            "{cell},{},{},{},{}",
+            summary.seed,
+            summary.ticks,
+            summary
+                .reason
+                .map_or_else(String::new, |r| csv_field(&r.to_string())),
+            csv_field(summary.winner.as_deref().unwrap_or(""))
+        )?;
+    }
+    file.flush()?;
+
+    let table_path = dir.join("sweep_summary.csv");
+    sweep::export_csv(&table_path, &cells, &sweep::aggregate(&cells, &runs))?;
+    eprintln!("Wrote {} and {}", runs_path.display(), table_path.display());
+    Ok(())
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use pd_core::{rng, Tournament};
+
+use crate::{arg_value, output_dir, strategies, USAGE};
+
+//plays a round robin tournament between the strategies and writes the table and the matrix
+pub fn run() -> Result<(), Box<dyn Error>> {
+    let tournament_path = std::env::args().nth(2).ok_or(USAGE)?;
+    let tournament = Tournament::load(&tournament_path)?;
+    let seed = match arg_value("--seed") {
+        Some(seed) => seed.parse().map_err(|_| format!("invalid seed {seed}"))?,
+        None => tournament.seed.unwrap_or_else(rng::random_seed),
+    };
+    let dir = output_dir()?;
+
+    eprintln!("Running tournament {tournament_path} with seed {seed}");
+    let result = tournament.run(seed, &strategies()?)?;
+    let prefix = format!("tournament_seed{seed}");
+    result.export_csv(&dir, &prefix)?;
+
+    print!("{result}");
+    eprintln!(
+        "Wrote {prefix}_standings.csv and {prefix}_matrix.csv to {}",
+        dir.display()
+    );
+    Ok(())
+}